@@ -0,0 +1,157 @@
+use crate::register::{RegisterBlock, UartRegisters};
+use crate::{BlockingUart, InterruptMask, set_interrupt_mask};
+use core::ops::Deref;
+use core::task::{Context, Poll, Waker};
+
+/// A lightweight asynchronous UART transmitter.
+///
+/// Writes are attempted directly; when the transmitter has no room, the waker is stored
+/// and the transmitter holding register empty interrupt is enabled.
+/// The interrupt handler must then call [`on_thre_interrupt`](Self::on_thre_interrupt)
+/// to wake the pending task, so this value is typically kept in a static shared
+/// with the handler, e.g. behind a critical section mutex.
+pub struct AsyncTx<UART> {
+    uart: BlockingUart<UART>,
+    waker: Option<Waker>,
+}
+
+impl<UART: UartRegisters> AsyncTx<UART> {
+    /// Creates a new AsyncTx from a BlockingUart.
+    pub fn new(uart: BlockingUart<UART>) -> Self {
+        AsyncTx { uart, waker: None }
+    }
+
+    /// Attempts to write data from the provided buffer to the UART.
+    ///
+    /// Returns `Poll::Ready` with the number of bytes written if at least one byte was accepted,
+    /// or if `buf` is empty. Otherwise registers the waker of `cx`, enables the
+    /// transmitter holding register empty interrupt, and returns `Poll::Pending`.
+    pub fn poll_write(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<usize> {
+        let len = self.uart.write(buf);
+        if len > 0 || buf.is_empty() {
+            return Poll::Ready(len);
+        }
+        match &self.waker {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            _ => self.waker = Some(cx.waker().clone()),
+        }
+        self.set_transmitter_empty_interrupt(true);
+        Poll::Pending
+    }
+
+    /// Handles the transmitter holding register empty interrupt.
+    ///
+    /// Disables the interrupt and wakes the task waiting in [`poll_write`](Self::poll_write), if any.
+    pub fn on_thre_interrupt(&mut self) {
+        self.set_transmitter_empty_interrupt(false);
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Consumes the AsyncTx, returning the wrapped BlockingUart.
+    pub fn into_inner(self) -> BlockingUart<UART> {
+        self.uart
+    }
+
+    /// Switches back to polled operation, returning the wrapped BlockingUart.
+    ///
+    /// The transmitter is drained, then every interrupt source is disabled and the pending
+    /// transmitter holding register empty and modem status interrupts are acknowledged.
+    /// The FIFOs are not reset and received data is left in place, so no byte is lost.
+    pub fn into_polled_mode(self) -> BlockingUart<UART> {
+        let uart = self.uart;
+        uart.flush();
+        set_interrupt_mask(uart.registers(), InterruptMask::default());
+        acknowledge_interrupts(uart.registers());
+        uart
+    }
+
+    fn set_transmitter_empty_interrupt(&self, enable: bool) {
+        let uart = self.uart.registers();
+        let ier = match enable {
+            true => uart.read_ier_dlh().enable_transmitter_empty_interrupt(),
+            false => uart.read_ier_dlh().disable_transmitter_empty_interrupt(),
+        };
+        unsafe {
+            uart.write_ier_dlh(ier);
+        }
+    }
+}
+
+impl<UART: UartRegisters> BlockingUart<UART> {
+    /// Switches to interrupt-driven operation, enabling the interrupt sources in `mask`.
+    ///
+    /// The transmitter is drained and the pending transmitter holding register empty and
+    /// modem status interrupts are acknowledged before the new mask is applied,
+    /// so the handler does not start with a stale event.
+    /// The transmitter holding register empty interrupt is managed by the returned [`AsyncTx`],
+    /// which enables it only while a write is pending, so it is left disabled whatever `mask` says.
+    /// The FIFOs are not reset and received data is left in place, so no byte is lost;
+    /// a pending receiver line status interrupt stays pending for the handler.
+    /// On PC-compatible boards OUT2 must be set as well, see [`enable_interrupts`](Self::enable_interrupts).
+    pub fn into_interrupt_mode(self, mask: InterruptMask) -> AsyncTx<UART> {
+        self.flush();
+        set_interrupt_mask(self.registers(), InterruptMask::default());
+        acknowledge_interrupts(self.registers());
+        let mask = InterruptMask {
+            transmitter_empty: false,
+            ..mask
+        };
+        set_interrupt_mask(self.registers(), mask);
+        AsyncTx::new(self)
+    }
+}
+
+/// Acknowledges the interrupts that reading IIR and MSR clears, without touching received data.
+fn acknowledge_interrupts(uart: &impl UartRegisters) {
+    uart.read_iir_fcr();
+    uart.read_msr();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use crate::mock::{mock_uart, set_lsr};
+
+    #[test]
+    fn async_tx_poll_write() {
+        let mock = mock_uart();
+        let mut tx = AsyncTx::new(BlockingUart::new(&mock, Config::new(), false));
+        let mut cx = Context::from_waker(Waker::noop());
+
+        // Test writing while the transmitter has room
+        assert_eq!(tx.poll_write(&mut cx, &[0x55]), Poll::Ready(1));
+
+        // Test a full transmitter registers the waker and enables the interrupt
+        set_lsr(&mock, 0x00);
+        assert_eq!(tx.poll_write(&mut cx, &[0x55]), Poll::Pending);
+        assert!(mock.ier_dlh.read().is_transmitter_empty_interrupt_enabled());
+
+        // Test the interrupt handler disables the interrupt again
+        tx.on_thre_interrupt();
+        assert!(!mock.ier_dlh.read().is_transmitter_empty_interrupt_enabled());
+    }
+
+    #[test]
+    fn async_tx_mode_switch() {
+        let mock = mock_uart();
+        let uart = BlockingUart::new(&mock, Config::new(), false);
+        let mask = InterruptMask {
+            received_data_available: true,
+            transmitter_empty: true,
+            ..InterruptMask::default()
+        };
+
+        // Test the transmitter empty interrupt is left to AsyncTx
+        let tx = uart.into_interrupt_mode(mask);
+        let ier = mock.ier_dlh.read();
+        assert!(ier.is_received_data_available_interrupt_enabled());
+        assert!(!ier.is_transmitter_empty_interrupt_enabled());
+
+        // Test going back to polled mode disables all interrupts
+        let uart = tx.into_polled_mode();
+        assert_eq!(uart.interrupt_mask(), InterruptMask::default());
+    }
+}
@@ -1,11 +1,19 @@
 #![allow(unused)]
 
-use crate::register::RegisterBlock;
+use crate::register::{IerDlh, Lcr, Mcr, MmioRegisters, Msr, RegisterBlock, Scr, UartRegisters};
+use crate::wait::{DefaultWait, WaitStrategy};
 use crate::{
-    Config, RbrThrDll, divisor, parity_mode, read_ready, set_divisor, set_parity_mode,
-    set_stop_bits, set_word_length, stop_bits, word_length, write_ready,
+    Baud, ChecksumAlgo, ChecksumError, Config, ConfigError, DmaMode, ExtendedConfig, FifoStatus,
+    Hertz, IirFcr, InterruptId, InterruptMask, LineStatus, Lsr, ModemStatus, Parity, ParityMode,
+    RbrThrDll, ReceiverTriggerLevel, StopBits, UartError, UartParams, Utf8LineError,
+    baud_to_divisor, bit_duration_ns, config, divisor, divisor_to_baud, interrupt_mask,
+    parity_mode, read_ready, set_config, set_divisor, set_interrupt_mask, with_dlab,
+    write_volatile_barrier,
 };
-use core::ops::Deref;
+use core::cell::Cell;
+use core::mem::MaybeUninit;
+use core::ops::{Deref, DerefMut};
+use embedded_hal::delay::DelayNs;
 use embedded_hal_nb::nb;
 use embedded_io::ErrorType;
 
@@ -14,11 +22,15 @@ use embedded_io::ErrorType;
 /// This function attempts to read data from the UART into the provided buffer.
 /// It will read as much data as possible until either the buffer is full or no more data is available.
 /// Returns the number of bytes actually read.
-fn blocking_read(uart: &RegisterBlock, buf: &mut [u8]) -> usize {
+///
+/// This is the hot loop of every bulk read: one LSR read and one RBR read per byte, as the 16550
+/// has no receive level register to read a whole burst blindly.
+#[inline]
+fn blocking_read(uart: &impl UartRegisters, buf: &mut [u8]) -> usize {
     let mut count = 0_usize;
     for ch in buf {
-        if uart.lsr.read().is_data_ready() {
-            *ch = uart.rbr_thr_dll.read().receiver_data();
+        if uart.read_lsr().is_data_ready() {
+            *ch = uart.read_rbr_thr_dll().receiver_data();
             count += 1;
         } else {
             break;
@@ -27,177 +39,3724 @@ fn blocking_read(uart: &RegisterBlock, buf: &mut [u8]) -> usize {
     count
 }
 
+/// Represents the order in which the Line Status Register and Receive Buffer Register are read.
+///
+/// In FIFO mode the parity, framing and break bits of the LSR describe the character
+/// at the front of the receiver FIFO, i.e. the one the next RBR read returns.
+/// On a standard 16550 the LSR must therefore be read before the RBR for an error
+/// to be associated with the right byte. Some derived parts instead latch the error
+/// of the character being read, and report it in the LSR after the RBR read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCheckOrder {
+    /// Read LSR, then RBR: the errors describe the byte about to be read (16550 behavior).
+    LsrThenRbr,
+    /// Read RBR, then LSR: the errors describe the byte just read.
+    RbrThenLsr,
+}
+
+/// Reads data from UART, stopping at the first receiver error.
+///
+/// The error bits of the Line Status Register are associated with each byte according to `order`.
+/// Bytes with parity, framing or break errors are discarded;
+/// on overrun the byte itself is intact and kept, but some later data was lost.
+/// Returns the number of bytes read, with the error if one stopped the read.
+fn checked_read(
+    uart: &impl UartRegisters,
+    buf: &mut [u8],
+    order: ErrorCheckOrder,
+    ignore_parity: bool,
+) -> Result<usize, (usize, UartError)> {
+    let mut count = 0_usize;
+    let mut next = None;
+    for ch in buf {
+        let lsr = next.take().unwrap_or_else(|| uart.read_lsr());
+        if !lsr.is_data_ready() {
+            break;
+        }
+        let data = uart.read_rbr_thr_dll().receiver_data();
+        let status = match order {
+            ErrorCheckOrder::LsrThenRbr => lsr,
+            ErrorCheckOrder::RbrThenLsr => *next.insert(uart.read_lsr()),
+        };
+        match rx_error(status, ignore_parity) {
+            None => {
+                *ch = data;
+                count += 1;
+            }
+            Some(UartError::Overrun) => {
+                *ch = data;
+                return Err((count + 1, UartError::Overrun));
+            }
+            Some(err) => return Err((count, err)),
+        }
+    }
+    Ok(count)
+}
+
+/// Gets the receiver error of a Line Status Register value, optionally ignoring parity errors.
+fn rx_error(lsr: Lsr, ignore_parity: bool) -> Option<UartError> {
+    match ignore_parity {
+        true => UartError::from_line_status(lsr.without_parity_error()),
+        false => UartError::from_line_status(lsr),
+    }
+}
+
+/// Depth of the FIFOs of a 16550A.
+const FIFO_DEPTH: usize = 16;
+
+/// Depth of the FIFOs of a 16750 in 64-byte mode.
+const FIFO_DEPTH_64: usize = 64;
+
+/// Represents the Line Status Register bit that tells the transmitter can take more data.
+///
+/// The default is [`Thre`](Self::Thre), or [`Temt`](Self::Temt) with the `temt` feature,
+/// and can be changed at run time with [`BlockingUart::set_write_strategy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WriteStrategy {
+    /// Wait for THRE, set as soon as the holding register or FIFO is empty.
+    ///
+    /// The next burst is written while the last character is still being shifted out,
+    /// so the line never goes idle between bursts.
+    #[cfg_attr(not(feature = "temt"), default)]
+    Thre,
+    /// Wait for TEMT, set only once the shift register is empty as well.
+    ///
+    /// This leaves a gap of up to one character time between bursts, but does not rely on THRE.
+    /// It is needed on parts where THRE does not mean the transmit FIFO is empty:
+    /// a DesignWare APB UART with the programmable THRE interrupt mode enabled
+    /// (see [`Cpr::has_programmable_thre`](crate::Cpr::has_programmable_thre)) reports a full FIFO
+    /// in the THRE bit instead, and some clone and FPGA soft-core UARTs set THRE while
+    /// the FIFO still holds data, so a whole FIFO burst overflows it.
+    #[cfg_attr(feature = "temt", default)]
+    Temt,
+}
+
+impl WriteStrategy {
+    /// Checks if a Line Status Register value allows the next burst to be written.
+    #[inline]
+    pub const fn is_ready(self, lsr: Lsr) -> bool {
+        match self {
+            WriteStrategy::Thre => lsr.is_transmitter_fifo_empty(),
+            WriteStrategy::Temt => lsr.is_transmitter_empty(),
+        }
+    }
+}
+
 /// Writes data to UART in a blocking manner.
 ///
 /// This function attempts to write data from the provided buffer to the UART.
 /// It will write as much data as possible until either all data is written or the FIFO becomes full.
+/// Each time `strategy` finds the transmitter ready, up to `burst` bytes are written: with FIFOs enabled
+/// this means the whole transmit FIFO is empty, so `burst` may be the FIFO depth; otherwise it must be 1.
 /// Returns the number of bytes actually written.
-fn blocking_write(uart: &RegisterBlock, buf: &[u8]) -> usize {
+///
+/// This is the hot loop of every write: the LSR is read once per burst, and within a burst
+/// each byte costs a single THR store, with no bounds check or register read-modify-write.
+#[inline]
+fn blocking_write(
+    uart: &impl UartRegisters,
+    buf: &[u8],
+    burst: usize,
+    strategy: WriteStrategy,
+) -> usize {
     let mut count = 0_usize;
-    for ch in buf {
-        if uart.lsr.read().is_transmitter_fifo_empty() {
-            let thr = RbrThrDll::default().set_transmitter_data(*ch);
+    for chunk in buf.chunks(burst.max(1)) {
+        if !strategy.is_ready(uart.read_lsr()) {
+            break;
+        }
+        for &ch in chunk {
             unsafe {
-                uart.rbr_thr_dll.write(thr);
+                uart.write_rbr_thr_dll(RbrThrDll::from_transmitter_data(ch));
             }
-            count += 1;
-        } else {
-            break;
         }
+        count += chunk.len();
     }
     count
 }
 
+/// Writes all data to UART, waiting for room in the transmitter as needed.
+fn blocking_write_all(
+    uart: &impl UartRegisters,
+    mut buf: &[u8],
+    burst: usize,
+    strategy: WriteStrategy,
+) {
+    while !buf.is_empty() {
+        let len = blocking_write(uart, buf, burst, strategy);
+        if len == 0 {
+            DefaultWait::wait();
+        }
+        buf = &buf[len..];
+    }
+}
+
+/// Reads a single byte from UART, waiting until one is available.
+fn blocking_read_byte(uart: &impl UartRegisters) -> u8 {
+    while !uart.read_lsr().is_data_ready() {
+        DefaultWait::wait();
+    }
+    uart.read_rbr_thr_dll().receiver_data()
+}
+
 /// Flushes the UART transmitter by waiting until all data has been sent.
 ///
 /// This function blocks until the transmitter is completely empty.
-fn blocking_flush(uart: &RegisterBlock) {
-    while !uart.lsr.read().is_transmitter_empty() {
+fn blocking_flush(uart: &impl UartRegisters) {
+    while !uart.read_lsr().is_transmitter_empty() {
+        DefaultWait::wait();
+    }
+}
+
+/// Represents the action taken when an echoed byte differs from the byte sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EchoMismatch {
+    /// Stop the transfer at the mismatching byte, leaving it unchanged in the buffer.
+    Stop,
+    /// Store the echoed byte in the buffer and continue.
+    Overwrite,
+}
+
+/// Represents the outcome of [`BlockingUart::read_line`] and [`BlockingUart::read_until`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadLine {
+    /// A full line was read; holds its length excluding the line terminator,
+    /// or including the delimiter when [`read_until`](BlockingUart::read_until) was asked to keep it.
+    Complete(usize),
+    /// The buffer filled before a newline or delimiter was seen; holds the buffer length.
+    /// The rest of the line is left unread.
+    Truncated(usize),
+}
+
+impl ReadLine {
+    /// Gets the number of bytes stored in the buffer.
+    pub const fn len(&self) -> usize {
+        match *self {
+            ReadLine::Complete(len) | ReadLine::Truncated(len) => len,
+        }
+    }
+
+    /// Checks if no bytes were stored in the buffer.
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Represents the UART variants told apart by [`BlockingUart::capabilities`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UartKind {
+    /// No scratch register, as on the original 8250.
+    Uart8250,
+    /// A scratch register but no working FIFOs.
+    Uart16450,
+    /// FIFOs reported as not functioning, as on the original 16550.
+    ///
+    /// The FIFOs of the 16550 before the A revision are broken and may lose or duplicate characters,
+    /// so it is best used as a 16450; see [`BlockingUart::enable_fifo`].
+    Uart16550,
+    /// 16-byte FIFOs.
+    Uart16550A,
+    /// 64-byte FIFOs, enabled through the FCR while DLAB is set.
+    Uart16750,
+}
+
+/// Represents the features found by [`BlockingUart::capabilities`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// The detected variant.
+    pub kind: UartKind,
+    /// The depth of the FIFOs in bytes, 1 without working FIFOs.
+    pub fifo_depth: usize,
+    /// Whether the 64-byte FIFO mode of the 16750 is available.
+    pub has_64byte_fifo: bool,
+    /// Whether the auto flow control enable bit of the MCR is implemented.
+    pub has_auto_flow: bool,
+    /// Whether a fractional divisor latch is present.
+    ///
+    /// The fractional divisor of DesignWare and similar parts sits outside the standard registers,
+    /// so it cannot be probed here; this is always false, and board knowledge has to fill it in.
+    pub has_dlf: bool,
+}
+
+/// Represents the state of the receive line, as classified by [`BlockingUart::line_health`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineHealth {
+    /// Nothing was received.
+    Idle,
+    /// Data was received, with fewer errors than the threshold.
+    Active,
+    /// Parity, framing or overrun errors reached the threshold.
+    Noisy,
+    /// Breaks reached the threshold, e.g. because the line is held low.
+    Break,
+}
+
+/// Represents the outcome of [`BlockingUart::ber_test`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BerResult {
+    /// The number of bytes sent.
+    pub bytes: usize,
+    /// The number of bytes not received back intact, including lost ones.
+    pub errors: usize,
+    /// The number of bits received wrong; a lost byte counts all 8 bits.
+    pub bit_errors: u64,
+}
+
+impl BerResult {
+    /// Gets the bit error rate, the fraction of bits received wrong.
+    ///
+    /// Returns 0 if no byte was sent.
+    pub fn bit_error_rate(&self) -> f32 {
+        match self.bytes {
+            0 => 0.0,
+            bytes => self.bit_errors as f32 / (bytes as f32 * 8.0),
+        }
+    }
+}
+
+/// Spins until the condition holds, or until `spin_limit` iterations have passed.
+///
+/// This always uses a spin loop regardless of the wait strategy, so that the limit bounds the time spent.
+/// Returns true if the condition was met.
+fn spin_until(spin_limit: u32, mut cond: impl FnMut() -> bool) -> bool {
+    for _ in 0..spin_limit {
+        if cond() {
+            return true;
+        }
         core::hint::spin_loop();
     }
+    cond()
+}
+
+/// Register accessor that records the receiver errors of every LSR read.
+///
+/// Reading the LSR clears its error bits, so they are merged into `sticky` on each read,
+/// see [`BlockingUart::sticky_errors`].
+struct Tracked<UART> {
+    regs: UART,
+    sticky: Cell<LineStatus>,
+}
+
+impl<UART> Tracked<UART> {
+    fn new(regs: UART) -> Self {
+        Tracked {
+            regs,
+            sticky: Cell::new(LineStatus::default()),
+        }
+    }
+}
+
+impl<UART: UartRegisters> UartRegisters for Tracked<UART> {
+    #[inline]
+    fn read_rbr_thr_dll(&self) -> RbrThrDll {
+        self.regs.read_rbr_thr_dll()
+    }
+
+    #[inline]
+    unsafe fn write_rbr_thr_dll(&self, val: RbrThrDll) {
+        unsafe { self.regs.write_rbr_thr_dll(val) }
+    }
+
+    #[inline]
+    fn read_ier_dlh(&self) -> IerDlh {
+        self.regs.read_ier_dlh()
+    }
+
+    #[inline]
+    unsafe fn write_ier_dlh(&self, val: IerDlh) {
+        unsafe { self.regs.write_ier_dlh(val) }
+    }
+
+    #[inline]
+    fn read_iir_fcr(&self) -> IirFcr {
+        self.regs.read_iir_fcr()
+    }
+
+    #[inline]
+    unsafe fn write_iir_fcr(&self, val: IirFcr) {
+        unsafe { self.regs.write_iir_fcr(val) }
+    }
+
+    #[inline]
+    fn read_lcr(&self) -> Lcr {
+        self.regs.read_lcr()
+    }
+
+    #[inline]
+    unsafe fn write_lcr(&self, val: Lcr) {
+        unsafe { self.regs.write_lcr(val) }
+    }
+
+    #[inline]
+    fn read_mcr(&self) -> Mcr {
+        self.regs.read_mcr()
+    }
+
+    #[inline]
+    unsafe fn write_mcr(&self, val: Mcr) {
+        unsafe { self.regs.write_mcr(val) }
+    }
+
+    #[inline]
+    fn read_lsr(&self) -> Lsr {
+        let lsr = self.regs.read_lsr();
+        let status = LineStatus::from(lsr);
+        let mut sticky = self.sticky.get();
+        sticky.overrun_error |= status.overrun_error;
+        sticky.parity_error |= status.parity_error;
+        sticky.framing_error |= status.framing_error;
+        sticky.break_interrupt |= status.break_interrupt;
+        sticky.fifo_error |= status.fifo_error;
+        self.sticky.set(sticky);
+        lsr
+    }
+
+    #[inline]
+    fn read_msr(&self) -> Msr {
+        self.regs.read_msr()
+    }
+
+    #[inline]
+    fn read_scr(&self) -> Scr {
+        self.regs.read_scr()
+    }
+
+    #[inline]
+    unsafe fn write_scr(&self, val: Scr) {
+        unsafe { self.regs.write_scr(val) }
+    }
 }
 
 /// A wrapper struct for UART that provides blocking operations.
 ///
 /// This struct implements blocking read and write operations for UART communication.
+/// Registers are accessed through [`UartRegisters`], so `UART` is usually a `&'static RegisterBlock`,
+/// but may be any other register backend.
 pub struct BlockingUart<UART> {
-    uart: UART,
+    uart: Tracked<UART>,
+    /// Shadow of the write-only FIFO Control Register.
+    fcr: IirFcr,
+    /// Number of bytes written per THRE assertion, see [`blocking_write`].
+    tx_burst: usize,
+    write_strategy: WriteStrategy,
+    /// Shadow of the OUT1 bit of the MCR.
+    out1: bool,
+    error_check_order: ErrorCheckOrder,
+    ignore_rx_parity: bool,
+    #[cfg(feature = "stats")]
+    tx_count: Cell<u64>,
+    #[cfg(feature = "stats")]
+    rx_count: Cell<u64>,
 }
 
-impl<UART: Deref<Target = RegisterBlock>> BlockingUart<UART> {
+impl<UART: UartRegisters> BlockingUart<UART> {
     /// Creates a new BlockingUart instance with the specified configuration.
     ///
     /// This function initializes the UART with the provided configuration parameters.
+    /// If [`Config::divisor`] is `None`, DLAB is not toggled and the divisor latch is not written,
+    /// so a baud rate inherited from firmware is kept while the line settings are applied.
     /// Returns a new BlockingUart instance.
     pub fn new(uart: UART, config: Config, enable_fifo: bool) -> Self {
-        set_divisor(&uart, config.divisor);
-        set_parity_mode(&uart, config.parity_mode);
-        set_stop_bits(&uart, config.stop_bits);
-        set_word_length(&uart, config.word_length);
+        set_config(&uart, &config);
 
+        // FCR is write-only: reading this address returns IIR, so start from a known value.
         let fcr = match enable_fifo {
-            true => uart.iir_fcr.read().enable_fifo(),
-            false => uart.iir_fcr.read().disable_fifo(),
+            true => IirFcr::default().enable_fifo(),
+            false => IirFcr::default().disable_fifo(),
         };
         unsafe {
-            uart.iir_fcr.write(fcr);
+            uart.write_iir_fcr(fcr);
         }
+        write_volatile_barrier();
 
         let ier = uart
-            .ier_dlh
-            .read()
+            .read_ier_dlh()
             .disable_modem_status_interrupt()
             .disable_receiver_line_status_interrupt()
             .disable_received_data_available_interrupt()
             .disable_transmitter_empty_interrupt();
         unsafe {
-            uart.ier_dlh.write(ier);
+            uart.write_ier_dlh(ier);
+        }
+
+        // Only fill the whole FIFO at once if the part reports its FIFOs as enabled;
+        // a 16450 ignores the FCR write. No interrupt is enabled, so reading IIR has no side effect.
+        let tx_burst = match enable_fifo && uart.read_iir_fcr().fifo_status() == FifoStatus::Enabled
+        {
+            true => FIFO_DEPTH,
+            false => 1,
+        };
+
+        let out1 = uart.read_mcr().out1();
+
+        BlockingUart {
+            uart: Tracked::new(uart),
+            fcr,
+            tx_burst,
+            write_strategy: WriteStrategy::default(),
+            out1,
+            error_check_order: ErrorCheckOrder::LsrThenRbr,
+            ignore_rx_parity: false,
+            #[cfg(feature = "stats")]
+            tx_count: Cell::new(0),
+            #[cfg(feature = "stats")]
+            rx_count: Cell::new(0),
+        }
+    }
+
+    /// Wraps a UART that is already configured, without writing any register.
+    ///
+    /// This keeps the settings left by a bootloader or ROM, including the baud rate,
+    /// and never toggles DLAB, so a console that is already printing is not disturbed.
+    /// Interrupt enables and FIFO settings are left as they are too.
+    /// The inherited settings can be read back later with [`config`](Self::config),
+    /// which briefly sets DLAB to read the divisor.
+    ///
+    /// As the FIFO Control Register is write-only, its shadow is seeded from the FIFO status bits of the IIR,
+    /// so methods that rewrite it keep the FIFOs on, and bursts are used if the FIFOs are functioning.
+    /// Reading the IIR acknowledges a pending transmitter holding register empty interrupt.
+    /// The receiver trigger level and DMA mode cannot be read back; the first FCR write sets them
+    /// to one byte and mode 0 unless they are set explicitly.
+    pub fn adopt(uart: UART) -> Self {
+        let iir = uart.read_iir_fcr();
+        let (fcr, tx_burst) = match (iir.fifo_status(), iir.is_64byte_fifo_enabled()) {
+            (FifoStatus::Enabled, true) => (
+                IirFcr::default().enable_fifo().enable_64byte_fifo(),
+                FIFO_DEPTH_64,
+            ),
+            (FifoStatus::Enabled, false) => (IirFcr::default().enable_fifo(), FIFO_DEPTH),
+            (FifoStatus::EnabledNonFunctioning, _) => (IirFcr::default().enable_fifo(), 1),
+            (FifoStatus::Disabled, _) => (IirFcr::default(), 1),
+        };
+        BlockingUart {
+            uart: Tracked::new(uart),
+            fcr,
+            tx_burst,
+            write_strategy: WriteStrategy::default(),
+            out1: false,
+            error_check_order: ErrorCheckOrder::LsrThenRbr,
+            ignore_rx_parity: false,
+            #[cfg(feature = "stats")]
+            tx_count: Cell::new(0),
+            #[cfg(feature = "stats")]
+            rx_count: Cell::new(0),
         }
+    }
 
-        BlockingUart { uart }
+    /// Writes bytes for a panic message, bypassing any lock that guards the UART.
+    ///
+    /// A panic handler may run while the code that panicked holds the lock around the
+    /// shared BlockingUart, e.g. a `critical_section::Mutex`, so taking it again would deadlock.
+    /// This writes through `uart` directly instead, usually a second handle to the same registers.
+    /// If DLAB was left set by an interrupted configuration change it is cleared first, so the bytes
+    /// are not written to the divisor latch. Each byte waits for THRE with a spin loop, whatever the
+    /// wait strategy, as interrupts may be disabled; then the transmitter is flushed, so the message
+    /// is on the line before the handler halts or resets.
+    ///
+    /// # Safety
+    ///
+    /// The caller must make sure nothing else can run and access the UART meanwhile,
+    /// as is usually the case in a panic handler, and must accept that the message may
+    /// interleave with data already queued by the interrupted code, or cut a transfer in half.
+    /// This is only meant for last-gasp output, where getting the message out matters more.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uart16550::{BlockingUart, RegisterBlock};
+    ///
+    /// fn on_panic(message: &[u8]) {
+    ///     // Another handle to the UART, whose BlockingUart may be locked by the panicking code.
+    ///     let regs = unsafe { &*(0x1000_0000 as *const RegisterBlock) };
+    ///     unsafe { BlockingUart::force_unlock_write(regs, message) };
+    /// }
+    /// ```
+    pub unsafe fn force_unlock_write(uart: UART, bytes: &[u8]) {
+        let lcr = uart.read_lcr();
+        if lcr.is_divisor_latch_access_enabled() {
+            unsafe {
+                uart.write_lcr(lcr.disable_divisor_latch_access());
+            }
+            write_volatile_barrier();
+        }
+        for &byte in bytes {
+            while !uart.read_lsr().is_transmitter_fifo_empty() {
+                core::hint::spin_loop();
+            }
+            unsafe {
+                uart.write_rbr_thr_dll(RbrThrDll::from_transmitter_data(byte));
+            }
+        }
+        while !uart.read_lsr().is_transmitter_empty() {
+            core::hint::spin_loop();
+        }
     }
 
     /// Returns the current configuration of the UART.
     ///
     /// This function reads all configuration parameters from the UART registers and returns them as a Config struct.
     pub fn config(uart: UART) -> Config {
-        let divisor = divisor(&uart);
-        let parity_mode = parity_mode(&uart);
-        let stop_bits = stop_bits(&uart);
-        let word_length = word_length(&uart);
-        Config {
-            divisor,
-            parity_mode,
-            stop_bits,
-            word_length,
+        config(&uart)
+    }
+
+    /// Gets the baud rate the hardware is currently running at, given the UART input clock in hertz.
+    ///
+    /// The divisor is read back from the divisor latch, so this reports the real rate
+    /// even after [`adopt`](Self::adopt), when the firmware left the baud rate in place.
+    /// DLAB is set for the read and LCR is always restored, so no setting is changed;
+    /// data written to the transmitter from an interrupt handler meanwhile would reach the divisor latch, though.
+    /// Returns 0 if the divisor latch holds 0.
+    pub fn current_baud(&self, clock_hz: u32) -> u32 {
+        divisor_to_baud(clock_hz, divisor(&self.uart))
+    }
+
+    /// Temporarily applies another configuration.
+    ///
+    /// The transmitter is drained, then the configuration is applied.
+    /// The returned guard dereferences to this BlockingUart, and restores the
+    /// original configuration when dropped, again after draining the transmitter.
+    pub fn temp_config(&mut self, config: Config) -> ConfigGuard<'_, UART> {
+        self.flush();
+        let original = crate::config(&self.uart);
+        set_config(&self.uart, &config);
+        ConfigGuard {
+            uart: self,
+            original,
         }
     }
 
+    /// Runs a closure with another configuration temporarily applied.
+    ///
+    /// The original configuration is restored when the closure returns, see [`temp_config`](Self::temp_config).
+    pub fn with_temp_config<R>(&mut self, config: Config, f: impl FnOnce(&mut Self) -> R) -> R {
+        let mut guard = self.temp_config(config);
+        f(&mut guard)
+    }
+
+    /// Runs a closure with another baud rate temporarily applied.
+    ///
+    /// This suits bootloader protocols that send the bulk payload at a higher baud rate.
+    /// The transmitter is drained and the divisor for `baud` is programmed, then the closure runs;
+    /// afterwards the transmitter is drained again and the original divisor is restored.
+    /// Bytes received around each switch straddle two baud rates and are garbage,
+    /// so the receiver is emptied after each divisor change.
+    /// The other line settings are left untouched.
+    pub fn with_temp_baud<R>(
+        &mut self,
+        clock: Hertz,
+        baud: Baud,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        self.flush();
+        let original = divisor(&self.uart);
+        set_divisor(&self.uart, baud_to_divisor(clock.0, baud.0));
+        self.discard_received();
+        let result = f(self);
+        self.flush();
+        set_divisor(&self.uart, original);
+        self.discard_received();
+        result
+    }
+
     /// Reads data from the UART into the provided buffer.
     ///
+    /// Each byte is taken after checking the data ready bit of the LSR, which is set while
+    /// at least one byte is in the receiver, whatever the FIFO trigger level. The trigger level only
+    /// gates the received data available interrupt, so polled reads see every byte, even when
+    /// fewer than the trigger level are waiting.
     /// Returns the number of bytes actually read.
     pub fn read(&self, buf: &mut [u8]) -> usize {
-        blocking_read(&self.uart, buf)
+        self.count_rx(blocking_read(&self.uart, buf))
     }
 
-    /// Writes data from the provided buffer to the UART.
+    /// Drains the data currently in the receiver into the provided buffer, without waiting.
     ///
-    /// Returns the number of bytes actually written.
-    pub fn write(&mut self, buf: &[u8]) -> usize {
-        blocking_write(&self.uart, buf)
+    /// This is the same as [`read`](Self::read), named for high-throughput polling loops:
+    /// both share one tight loop that reads while data ready holds, up to the buffer length.
+    /// Returns the number of bytes read.
+    #[inline]
+    pub fn drain_available(&mut self, buf: &mut [u8]) -> usize {
+        self.read(buf)
     }
 
-    /// Flushes the UART transmitter.
+    /// Reads data from the UART into a possibly uninitialized buffer, without waiting.
     ///
-    /// This function ensures all data has been transmitted before returning.
-    pub fn flush(&self) {
-        blocking_flush(&self.uart)
+    /// This is [`read`](Self::read) for large staging buffers that are not worth zeroing first.
+    /// Returns the filled start of the buffer, which only covers the bytes actually received;
+    /// the rest of the buffer is left untouched and may still be uninitialized.
+    pub fn read_uninit<'a>(&mut self, buf: &'a mut [MaybeUninit<u8>]) -> &'a mut [u8] {
+        let mut count = 0_usize;
+        for slot in buf.iter_mut() {
+            if !self.uart.read_lsr().is_data_ready() {
+                break;
+            }
+            slot.write(self.uart.read_rbr_thr_dll().receiver_data());
+            count += 1;
+        }
+        self.count_rx(count);
+        // The first `count` elements were written above, and `MaybeUninit<u8>` has the layout of `u8`.
+        unsafe { core::slice::from_raw_parts_mut(buf.as_mut_ptr().cast::<u8>(), count) }
     }
-}
 
-impl<UART: Deref<Target = RegisterBlock>> ErrorType for BlockingUart<UART> {
-    type Error = core::convert::Infallible;
-}
+    /// Reads data from the UART like [`read`](Self::read), waiting `gap_ns` nanoseconds between bytes.
+    ///
+    /// This is rarely needed: it slows draining on purpose, for sensor protocols that expect
+    /// the master to pace its reads, or to give a sender time to react to flow control.
+    /// After each byte, `delay` waits for the gap before the data ready bit is checked again,
+    /// so a byte arriving during the gap is still read. Other reads are never paced.
+    /// Returns the number of bytes read.
+    pub fn read_paced(&mut self, buf: &mut [u8], gap_ns: u32, delay: &mut impl DelayNs) -> usize {
+        let mut count = 0_usize;
+        for slot in buf.iter_mut() {
+            if count > 0 {
+                delay.delay_ns(gap_ns);
+            }
+            if !self.uart.read_lsr().is_data_ready() {
+                break;
+            }
+            *slot = self.uart.read_rbr_thr_dll().receiver_data();
+            count += 1;
+        }
+        self.count_rx(count)
+    }
 
-impl<UART: Deref<Target = RegisterBlock>> embedded_io::Read for BlockingUart<UART> {
-    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
-        Ok(blocking_read(&self.uart, buf))
+    /// Reads one line into the provided buffer, blocking until it is complete.
+    ///
+    /// Bytes are read until a `\n`, which is not stored; a `\r` right before it is removed too.
+    /// If the buffer fills up first, returns [`ReadLine::Truncated`] and the rest of the line is left unread.
+    pub fn read_line(&mut self, buf: &mut [u8]) -> ReadLine {
+        match self.read_until(b'\n', buf, false) {
+            ReadLine::Complete(len) if len > 0 && buf[len - 1] == b'\r' => {
+                ReadLine::Complete(len - 1)
+            }
+            line => line,
+        }
     }
-}
 
-impl<UART: Deref<Target = RegisterBlock>> embedded_io::Write for BlockingUart<UART> {
-    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
-        Ok(blocking_write(&self.uart, buf))
+    /// Reads into the provided buffer until `delimiter`, blocking until it arrives.
+    ///
+    /// This generalizes [`read_line`](Self::read_line) to protocols delimited by other bytes,
+    /// such as `\0`, `;` or ETX. If `include_delimiter` is set, the delimiter is stored and counted
+    /// in the returned length, which needs room for it in the buffer; otherwise it is consumed but not stored,
+    /// so a delimiter received first gives `Complete(0)`.
+    /// If the buffer fills up first, returns [`ReadLine::Truncated`] and the rest is left unread.
+    pub fn read_until(
+        &mut self,
+        delimiter: u8,
+        buf: &mut [u8],
+        include_delimiter: bool,
+    ) -> ReadLine {
+        let mut len = 0_usize;
+        while len < buf.len() {
+            let byte = blocking_read_byte(&self.uart);
+            self.count_rx(1);
+            if byte == delimiter && !include_delimiter {
+                return ReadLine::Complete(len);
+            }
+            buf[len] = byte;
+            len += 1;
+            if byte == delimiter {
+                return ReadLine::Complete(len);
+            }
+        }
+        ReadLine::Truncated(len)
     }
 
-    fn flush(&mut self) -> Result<(), Self::Error> {
-        blocking_flush(&self.uart);
-        Ok(())
+    /// Reads one line of UTF-8 text into the provided buffer, blocking until it is complete.
+    ///
+    /// The line is read as by [`read_line`](Self::read_line) and then validated, so the caller
+    /// gets a `&str` directly. A line that ends inside a multibyte sequence, including one cut
+    /// by the end of the buffer, gives [`Utf8LineError::TruncatedMultibyte`], while a byte
+    /// that is not valid UTF-8 gives [`Utf8LineError::InvalidByte`].
+    /// A valid line that does not fit the buffer gives [`Utf8LineError::TooLong`].
+    pub fn read_line_str<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a str, Utf8LineError> {
+        let line = self.read_line(buf);
+        let bytes = &buf[..line.len()];
+        match core::str::from_utf8(bytes) {
+            Ok(_) if matches!(line, ReadLine::Truncated(_)) => Err(Utf8LineError::TooLong {
+                valid_up_to: bytes.len(),
+            }),
+            Ok(s) => Ok(s),
+            Err(e) => match e.error_len() {
+                Some(_) => Err(Utf8LineError::InvalidByte {
+                    valid_up_to: e.valid_up_to(),
+                }),
+                None => Err(Utf8LineError::TruncatedMultibyte {
+                    valid_up_to: e.valid_up_to(),
+                }),
+            },
+        }
     }
-}
 
-impl<UART: Deref<Target = RegisterBlock>> embedded_io::ReadReady for BlockingUart<UART> {
-    fn read_ready(&mut self) -> Result<bool, Self::Error> {
-        Ok(read_ready(&self.uart))
+    /// Reads one frame delimited by an idle gap into the provided buffer.
+    ///
+    /// Bytes are read until `idle_polls` consecutive polls of the line status register find no data,
+    /// or until the buffer is full, and the number of bytes read is returned.
+    /// The gap is also counted before the first byte, so 0 is returned when nothing arrives.
+    /// This is the polled counterpart of the character timeout interrupt, suited to
+    /// delimiter-less protocols such as Modbus RTU.
+    ///
+    /// Without a clock, polls stand in for time: a gap of `t` seconds is about `t / t_poll` polls,
+    /// where `t_poll` is the time one iteration takes on the target, mostly the register access.
+    /// At `baud`, a 10-bit character takes `10 / baud` seconds, so the 3.5 character gap of
+    /// Modbus RTU is about `35 / (baud * t_poll)` polls. Choose `idle_polls` comfortably above
+    /// one character time, or a slow sender will be split into several frames.
+    pub fn read_frame(&mut self, buf: &mut [u8], idle_polls: u32) -> usize {
+        let mut len = 0_usize;
+        let mut idle = 0_u32;
+        while len < buf.len() {
+            if self.uart.read_lsr().is_data_ready() {
+                buf[len] = self.uart.read_rbr_thr_dll().receiver_data();
+                len += 1;
+                idle = 0;
+            } else if idle >= idle_polls {
+                break;
+            } else {
+                idle += 1;
+            }
+        }
+        self.count_rx(len)
     }
-}
 
-impl<UART: Deref<Target = RegisterBlock>> embedded_io::WriteReady for BlockingUart<UART> {
-    fn write_ready(&mut self) -> Result<bool, Self::Error> {
-        Ok(write_ready(&self.uart))
+    /// Reads up to `buf.len()` bytes, waiting at most `spin_per_byte` iterations for each one.
+    ///
+    /// This tolerates short gaps between bytes without hanging on an idle line:
+    /// reading stops at the first byte that does not arrive within its budget,
+    /// and the number of bytes read is returned.
+    /// The budget is per byte, not for the whole call, so each byte that arrives resets it;
+    /// a slow but steady sender can keep this reading until the buffer is full, which a total budget
+    /// would cut short. The wait is a spin loop regardless of the wait strategy, see [`read_frame`](Self::read_frame)
+    /// for how iterations relate to time.
+    pub fn read_bounded(&mut self, buf: &mut [u8], spin_per_byte: u32) -> usize {
+        let mut len = 0_usize;
+        while len < buf.len() {
+            if !spin_until(spin_per_byte, || self.uart.read_lsr().is_data_ready()) {
+                break;
+            }
+            buf[len] = self.uart.read_rbr_thr_dll().receiver_data();
+            len += 1;
+        }
+        self.count_rx(len)
     }
-}
 
-impl<UART: Deref<Target = RegisterBlock>> embedded_hal_nb::serial::ErrorType
-    for BlockingUart<UART>
-{
-    type Error = core::convert::Infallible;
-}
+    /// Forwards received data to another writer, such as a second UART.
+    ///
+    /// Only bytes already available in the receiver are taken, up to `max`,
+    /// so this never waits for incoming data. Each chunk read is written out completely before the next one.
+    /// Returns the number of bytes forwarded, or the first error of the writer.
+    pub fn copy_to<W: embedded_io::Write>(
+        &mut self,
+        writer: &mut W,
+        max: usize,
+    ) -> Result<usize, W::Error> {
+        let mut count = 0_usize;
+        let mut chunk = [0; 16];
+        while count < max {
+            let len = self.read(&mut chunk[..(max - count).min(16)]);
+            if len == 0 {
+                break;
+            }
+            let mut rest = &chunk[..len];
+            while !rest.is_empty() {
+                let written = writer.write(rest)?;
+                rest = &rest[written..];
+            }
+            count += len;
+        }
+        Ok(count)
+    }
 
-impl<UART: Deref<Target = RegisterBlock>> embedded_hal_nb::serial::Read for BlockingUart<UART> {
-    fn read(&mut self) -> nb::Result<u8, Self::Error> {
-        let mut buf = [0];
-        let len = blocking_read(&self.uart, &mut buf);
-        match len {
-            0 => Err(nb::Error::WouldBlock),
-            _ => Ok(buf[0]),
+    /// Sets the receiver FIFO trigger level.
+    ///
+    /// The received data available interrupt fires once this many bytes are in the FIFO,
+    /// so a higher level coalesces interrupts on a busy line; the other FIFO settings are kept.
+    /// With a continuous stream, the interrupt rate is one per byte at [`Char1`](ReceiverTriggerLevel::Char1),
+    /// and one per 4, 8 or 14 bytes at the quarter, half and almost full levels of a 16-byte FIFO,
+    /// plus one character timeout interrupt at the end of each burst.
+    pub fn set_receiver_trigger_level(&mut self, level: ReceiverTriggerLevel) {
+        self.fcr = self.fcr.set_receiver_trigger_level(level);
+        unsafe {
+            self.uart.write_iir_fcr(self.fcr);
         }
+        write_volatile_barrier();
     }
-}
 
-impl<UART: Deref<Target = RegisterBlock>> embedded_hal_nb::serial::Write for BlockingUart<UART> {
-    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
-        let len = blocking_write(&self.uart, &[word]);
-        match len {
-            0 => Err(nb::Error::WouldBlock),
-            _ => Ok(()),
+    /// Selects the DMA mode signalled on the TXRDY and RXRDY pins, see [`DmaMode`].
+    ///
+    /// This only has an effect on parts that expose those pins, and only when they are wired
+    /// to a DMA controller that uses the hardware handshake. Multi-transfer mode relies on the FIFOs.
+    /// The other FIFO settings are kept, as the FCR is written from its shadow.
+    pub fn set_dma_mode(&mut self, mode: DmaMode) {
+        self.fcr = self.fcr.set_dma_mode(mode);
+        unsafe {
+            self.uart.write_iir_fcr(self.fcr);
         }
+        write_volatile_barrier();
     }
 
-    fn flush(&mut self) -> nb::Result<(), Self::Error> {
-        match self.uart.lsr.read().is_transmitter_empty() {
-            true => Ok(()),
-            false => Err(nb::Error::WouldBlock),
+    /// Clears the receiver FIFO, keeping the transmitter FIFO.
+    ///
+    /// Useful to recover from an overrun without losing data queued for transmission.
+    /// Only the receiver reset bit is set in the FCR write; the enable and trigger level
+    /// come from the shadow of the FCR, so they are kept. The reset bit clears itself.
+    /// The receiver shift register is not cleared, so a character being received still arrives.
+    pub fn clear_rx_fifo(&mut self) {
+        unsafe {
+            self.uart.write_iir_fcr(self.fcr.reset_receiver_fifo());
+        }
+        write_volatile_barrier();
+    }
+
+    /// Clears the transmitter FIFO, keeping the receiver FIFO.
+    ///
+    /// Data queued for transmission is lost; the character in the transmitter shift register
+    /// is still sent. The other FIFO settings are kept, see [`clear_rx_fifo`](Self::clear_rx_fifo).
+    pub fn clear_tx_fifo(&mut self) {
+        unsafe {
+            self.uart.write_iir_fcr(self.fcr.reset_transmitter_fifo());
+        }
+        write_volatile_barrier();
+    }
+
+    /// Sets the receiver FIFO trigger level and enables auto flow control in one step.
+    ///
+    /// With auto flow control the trigger level doubles as the flow control threshold:
+    /// RTS is deasserted once the receiver FIFO reaches it, and asserted again when the FIFO is empty.
+    /// Choosing the two separately makes it easy to end up with a threshold that was never intended.
+    /// RTS is set in the MCR as well, as parts such as the 16750 only drive RTS automatically when it is,
+    /// and CTS then gates the transmitter. The other FIFO settings are kept.
+    /// Returns [`ConfigError::FifoDisabled`] without touching any register unless FIFOs were enabled
+    /// and confirmed through the IIR, by [`new`](Self::new) or [`enable_fifo`](Self::enable_fifo),
+    /// since flow control has no threshold to act on then.
+    pub fn configure_auto_flow(
+        &mut self,
+        trigger: ReceiverTriggerLevel,
+    ) -> Result<(), ConfigError> {
+        if !self.is_fifo_enabled() {
+            return Err(ConfigError::FifoDisabled);
+        }
+        self.set_receiver_trigger_level(trigger);
+        let mcr = self
+            .uart
+            .read_mcr()
+            .set_request_to_send(true)
+            .enable_auto_flow_control();
+        unsafe {
+            self.uart.write_mcr(mcr);
+        }
+        write_volatile_barrier();
+        Ok(())
+    }
+
+    /// Enables or disables the FIFOs after construction.
+    ///
+    /// The trigger level comes from the shadow of the FCR, so it is kept.
+    /// Enabling is verified by reading the FIFO status bits of the IIR back after the FCR write,
+    /// which acknowledges a pending transmitter holding register empty interrupt.
+    /// Returns [`UartError::Unsupported`] if the part did not enable its FIFOs, e.g. because it is a 16450,
+    /// which ignores the FCR; the FCR is then restored to its previous value and writes stay byte by byte.
+    /// Disabling also turns off the 64-byte mode of a 16750.
+    ///
+    /// The original 16550, without the A suffix, has FIFOs that lose or duplicate characters,
+    /// and reports them as [`FifoStatus::EnabledNonFunctioning`]. Enabling is refused on it the same way,
+    /// so it runs as a 16450; [`force_enable_fifo`](Self::force_enable_fifo) overrides this.
+    pub fn enable_fifo(&mut self, enable: bool) -> Result<(), UartError> {
+        match enable {
+            true => self.switch_fifo_on(false),
+            false => {
+                self.fcr = self.fcr.disable_fifo().disable_64byte_fifo();
+                unsafe {
+                    self.uart.write_iir_fcr(self.fcr);
+                }
+                write_volatile_barrier();
+                self.tx_burst = 1;
+                Ok(())
+            }
+        }
+    }
+
+    /// Enables the FIFOs even on an original 16550, whose FIFOs are known to be broken.
+    ///
+    /// This is [`enable_fifo`](Self::enable_fifo), except that [`FifoStatus::EnabledNonFunctioning`]
+    /// is accepted, for parts that report it but are known to work, e.g. some clones.
+    /// Parts that report FIFOs disabled are still refused with [`UartError::Unsupported`].
+    pub fn force_enable_fifo(&mut self) -> Result<(), UartError> {
+        self.switch_fifo_on(true)
+    }
+
+    /// Enables the FIFOs and checks the FIFO status, restoring the FCR if it is not accepted.
+    fn switch_fifo_on(&mut self, force: bool) -> Result<(), UartError> {
+        let fcr = self.fcr.enable_fifo();
+        unsafe {
+            self.uart.write_iir_fcr(fcr);
+        }
+        write_volatile_barrier();
+        let accepted = match self.fifo_status() {
+            FifoStatus::Enabled => true,
+            FifoStatus::EnabledNonFunctioning => force,
+            FifoStatus::Disabled => false,
+        };
+        if !accepted {
+            unsafe {
+                self.uart.write_iir_fcr(self.fcr);
+            }
+            write_volatile_barrier();
+            return Err(UartError::Unsupported);
+        }
+        self.fcr = fcr;
+        self.tx_burst = match fcr.is_64byte_fifo_enabled() {
+            true => FIFO_DEPTH_64,
+            false => FIFO_DEPTH,
+        };
+        Ok(())
+    }
+
+    /// Enables or disables the 64-byte FIFO mode of a 16750.
+    ///
+    /// The 16750 only accepts the 64-byte FIFO bit of the FCR while DLAB is set,
+    /// and silently ignores it otherwise; this is easy to miss, as every other FCR bit is written with DLAB clear.
+    /// DLAB is therefore set around the FCR write, and LCR restored afterwards.
+    /// The other FIFO settings are kept. FIFOs must have been enabled with [`new`](Self::new)
+    /// or [`enable_fifo`](Self::enable_fifo). The result is checked in the IIR, which acknowledges a pending transmitter holding register empty interrupt.
+    /// Returns [`UartError::Unsupported`] if the part did not enable 64-byte FIFOs, e.g. because it is not a 16750;
+    /// the FCR is then restored to its previous value.
+    pub fn enable_64byte_fifo(&mut self, enable: bool) -> Result<(), UartError> {
+        let fcr = match enable {
+            true => self.fcr.enable_64byte_fifo(),
+            false => self.fcr.disable_64byte_fifo(),
+        };
+        self.write_fcr_with_dlab(fcr);
+        if !enable {
+            self.fcr = fcr;
+            self.tx_burst = self.tx_burst.min(FIFO_DEPTH);
+            return Ok(());
         }
+        let iir = self.uart.read_iir_fcr();
+        if !(iir.is_fifos_enabled() && iir.is_64byte_fifo_enabled()) {
+            self.write_fcr_with_dlab(self.fcr);
+            return Err(UartError::Unsupported);
+        }
+        self.fcr = fcr;
+        self.tx_burst = FIFO_DEPTH_64;
+        Ok(())
+    }
+
+    /// Writes the FCR while DLAB is set, restoring LCR afterwards.
+    fn write_fcr_with_dlab(&self, fcr: IirFcr) {
+        with_dlab(&self.uart, |uart| {
+            unsafe {
+                uart.write_iir_fcr(fcr);
+            }
+            write_volatile_barrier();
+        });
+    }
+
+    /// Drains the receiver FIFO in an interrupt handler.
+    ///
+    /// Reads until the data ready bit clears or the buffer is full, which acknowledges both
+    /// the received data available and the character timeout interrupts.
+    /// Returns the number of bytes read.
+    ///
+    /// # Example
+    ///
+    /// The high-throughput receive recipe: a high trigger level, receive interrupts enabled
+    /// (which include the character timeout), and the whole FIFO drained on each interrupt:
+    ///
+    /// ```
+    /// use uart16550::{BlockingUart, ReceiverTriggerLevel, RegisterBlock};
+    ///
+    /// fn setup(uart: &mut BlockingUart<&'static RegisterBlock>) {
+    ///     uart.set_receiver_trigger_level(ReceiverTriggerLevel::FifoFullSub2);
+    ///     let regs = uart.register_block();
+    ///     let ier = regs.ier_dlh.read().enable_received_data_available_interrupt();
+    ///     unsafe { regs.ier_dlh.write(ier) };
+    /// }
+    ///
+    /// fn on_uart_interrupt(uart: &BlockingUart<&'static RegisterBlock>, buf: &mut [u8]) -> usize {
+    ///     uart.drain_fifo_on_interrupt(buf)
+    /// }
+    /// ```
+    pub fn drain_fifo_on_interrupt(&self, buf: &mut [u8]) -> usize {
+        self.read(buf)
+    }
+
+    /// Reads data from the UART, checking each byte for receiver errors.
+    ///
+    /// The Line Status Register is inspected for each byte taken from the receiver buffer,
+    /// in the order set by [`set_error_check_order`](Self::set_error_check_order).
+    /// Returns `Ok` with the number of bytes read if no error was seen, or `Err` with
+    /// the number of good bytes stored in `buf` before the first parity, framing, break or overrun error.
+    /// The erroneous byte is discarded, except on overrun where the byte is valid and counted.
+    /// In FIFO mode, [`has_fifo_error`](Self::has_fifo_error) tells beforehand whether any byte waiting has an error.
+    pub fn read_checked(&mut self, buf: &mut [u8]) -> Result<usize, (usize, UartError)> {
+        let result = checked_read(
+            &self.uart,
+            buf,
+            self.error_check_order,
+            self.ignore_rx_parity,
+        );
+        self.count_rx(match result {
+            Ok(len) | Err((len, _)) => len,
+        });
+        result
+    }
+
+    /// Passes each received byte to `sink` while data is available.
+    ///
+    /// This drains the receiver without a staging buffer, e.g. straight into a protocol parser.
+    /// It returns as soon as the receiver is empty, so a peer sending continuously keeps it running.
+    /// Returns the number of bytes passed to `sink`.
+    pub fn poll_into(&mut self, mut sink: impl FnMut(u8)) -> usize {
+        let mut count = 0_usize;
+        while self.uart.read_lsr().is_data_ready() {
+            sink(self.uart.read_rbr_thr_dll().receiver_data());
+            count += 1;
+        }
+        self.count_rx(count)
+    }
+
+    /// Passes each received byte or receiver error to `sink` while data is available.
+    ///
+    /// Errors are associated with bytes as in [`read_checked`](Self::read_checked):
+    /// a byte with a parity, framing or break error is replaced by the error,
+    /// and on overrun the byte is passed first, followed by [`UartError::Overrun`] for the data lost after it.
+    /// Returns the number of good bytes passed to `sink`.
+    pub fn poll_into_checked(&mut self, mut sink: impl FnMut(Result<u8, UartError>)) -> usize {
+        let mut count = 0_usize;
+        let mut next = None;
+        loop {
+            let lsr = next.take().unwrap_or_else(|| self.uart.read_lsr());
+            if !lsr.is_data_ready() {
+                break;
+            }
+            let data = self.uart.read_rbr_thr_dll().receiver_data();
+            let status = match self.error_check_order {
+                ErrorCheckOrder::LsrThenRbr => lsr,
+                ErrorCheckOrder::RbrThenLsr => *next.insert(self.uart.read_lsr()),
+            };
+            match rx_error(status, self.ignore_rx_parity) {
+                None => {
+                    sink(Ok(data));
+                    count += 1;
+                }
+                Some(UartError::Overrun) => {
+                    sink(Ok(data));
+                    sink(Err(UartError::Overrun));
+                    count += 1;
+                }
+                Some(err) => sink(Err(err)),
+            }
+        }
+        self.count_rx(count)
+    }
+
+    /// Sets the order in which line status and received data are read by [`read_checked`](Self::read_checked).
+    ///
+    /// Defaults to [`ErrorCheckOrder::LsrThenRbr`], which is correct for the standard 16550.
+    pub fn set_error_check_order(&mut self, order: ErrorCheckOrder) {
+        self.error_check_order = order;
+    }
+
+    /// Sets whether parity errors are ignored by the checked reads.
+    ///
+    /// The 16550 has one parity setting for both directions, so parity cannot be generated on transmit
+    /// but left unchecked on receive in hardware. This is the software workaround for peers that
+    /// get parity wrong: the hardware still flags the error in the Line Status Register, but
+    /// [`read_checked`](Self::read_checked) and [`poll_into_checked`](Self::poll_into_checked) then keep
+    /// the byte as valid instead of reporting [`UartError::Parity`]. Other errors are still reported,
+    /// and the parity error still shows in [`line_status`](Self::line_status) and [`sticky_errors`](Self::sticky_errors).
+    pub fn set_ignore_rx_parity(&mut self, ignore: bool) {
+        self.ignore_rx_parity = ignore;
+    }
+
+    /// Writes data from the provided buffer to the UART.
+    ///
+    /// If FIFOs were enabled by [`new`](Self::new) and the part confirmed it in the IIR,
+    /// a whole FIFO's worth of bytes is written each time the transmit FIFO is seen empty;
+    /// otherwise THRE is checked before every byte.
+    /// This never blocks: it returns the number of bytes accepted, which is 0 if the transmitter was full,
+    /// so callers loop or wait for room themselves.
+    ///
+    /// The [`embedded_io::Write`] implementation differs, as that trait forbids `Ok(0)` for a non-empty buffer
+    /// and its `write_all` panics on it: it waits until at least one byte is accepted, then returns the partial count.
+    /// Non-blocking generic code checks [`embedded_io::WriteReady`] first.
+    pub fn write(&mut self, buf: &[u8]) -> usize {
+        self.count_tx(blocking_write(
+            &self.uart,
+            buf,
+            self.tx_burst,
+            self.write_strategy,
+        ))
+    }
+
+    /// Writes as much of a slice as the transmitter accepts, in the style of [`nb`].
+    ///
+    /// Returns `Ok(n)` with the number of bytes accepted, and `WouldBlock` if not a single byte fit,
+    /// so an nb-based scheduler can yield until the transmitter has room.
+    /// An empty slice returns `Ok(0)`. As for [`write`](Self::write), nothing waits.
+    ///
+    /// # Example
+    ///
+    /// Driving a whole message to completion from a cooperative state machine:
+    ///
+    /// ```
+    /// use embedded_hal_nb::nb;
+    /// use uart16550::{BlockingUart, RegisterBlock};
+    ///
+    /// /// Returns true once the whole message is queued.
+    /// fn poll_send(uart: &mut BlockingUart<&'static RegisterBlock>, rest: &mut &[u8]) -> bool {
+    ///     while !rest.is_empty() {
+    ///         match uart.write_nb_all(rest) {
+    ///             Ok(n) => *rest = &rest[n..],
+    ///             // No room: yield to the scheduler and call again later.
+    ///             Err(nb::Error::WouldBlock) => return false,
+    ///         }
+    ///     }
+    ///     true
+    /// }
+    /// ```
+    pub fn write_nb_all(&mut self, buf: &[u8]) -> nb::Result<usize, core::convert::Infallible> {
+        match self.write(buf) {
+            0 if !buf.is_empty() => Err(nb::Error::WouldBlock),
+            len => Ok(len),
+        }
+    }
+
+    /// Searches for the baud rate of an incoming stream by trying each candidate in turn.
+    ///
+    /// A standard 16550 does not expose the level of its receive line, so bit times cannot be measured in software.
+    /// Instead, for each candidate the divisor is programmed, and the receiver waits up to `timeout_us`
+    /// microseconds for a byte. The candidate is accepted when the byte equals `sync`
+    /// and was received without error; the host should repeat `sync` (e.g. `0x0D`) until it is answered.
+    /// Returns the matching baud rate with its divisor applied, or `None` with the original divisor restored.
+    ///
+    /// Parts with a hardware auto-baud unit can use it instead,
+    /// e.g. [`LpcRegisterBlock::start_autobaud`](crate::LpcRegisterBlock::start_autobaud).
+    pub fn autobaud_scan(
+        &mut self,
+        clock: Hertz,
+        candidates: &[Baud],
+        sync: u8,
+        delay: &mut impl DelayNs,
+        timeout_us: u32,
+    ) -> Option<Baud> {
+        let original = divisor(&self.uart);
+        for &baud in candidates {
+            set_divisor(&self.uart, baud_to_divisor(clock.0, baud.0));
+            while self.uart.read_lsr().is_data_ready() {
+                self.uart.read_rbr_thr_dll();
+            }
+            for _ in 0..timeout_us {
+                let lsr = self.uart.read_lsr();
+                if lsr.is_data_ready() {
+                    let data = self.uart.read_rbr_thr_dll().receiver_data();
+                    if data == sync && UartError::from_line_status(lsr).is_none() {
+                        return Some(baud);
+                    }
+                    break;
+                }
+                delay.delay_us(1);
+            }
+        }
+        set_divisor(&self.uart, original);
+        None
+    }
+
+    /// Measures the baud rate of an incoming break followed by `0x55`, returning the matching divisor.
+    ///
+    /// The receiver runs at its fastest rate (divisor 1) during the measurement. A `0x55` frame
+    /// alternates the line every bit, from the start bit on, so each of its five low bits starts one
+    /// character in the fast receiver; as the receiver completes each character a fixed time after
+    /// its falling edge, the data ready events are two bit times apart, and the first to the last span 8 bits.
+    /// `timer` returns a timestamp in nanoseconds, and is read each time the LSR is polled.
+    /// The break is recognized by the break interrupt bit; the five characters after it are timed,
+    /// and the measurement is rejected unless their spacing is even to within a quarter.
+    ///
+    /// Since each low bit must outlast one character of the fast receiver, only rates up to about
+    /// a fifth of `clock_hz / 16` can be measured. For the divisor to round to the right value,
+    /// the timer resolution and the poll loop latency together must stay well below a sixth of a bit time,
+    /// e.g. 17 µs at 9600 baud. Interrupts taken during the measurement add to the latency.
+    ///
+    /// Returns `None` if no measurement completes within `timeout_ns` from the call, even while bytes keep arriving,
+    /// if the spacing is uneven, if `timer` wraps or goes backwards, or if the divisor is out of range. The original divisor is restored either way, stale and leftover
+    /// received bytes are discarded, and the sticky errors are left as they were; the caller applies the result.
+    pub fn autobaud_measure(
+        &mut self,
+        mut timer: impl FnMut() -> u64,
+        clock_hz: u32,
+        timeout_ns: u64,
+    ) -> Option<u16> {
+        let original = divisor(&self.uart);
+        let sticky = self.uart.sticky.get();
+        set_divisor(&self.uart, 1);
+        self.discard_received();
+        let deadline = timer().saturating_add(timeout_ns);
+        let bit_ns = self.measure_bit_ns(&mut timer, deadline);
+        set_divisor(&self.uart, original);
+        self.discard_received();
+        self.uart.sticky.set(sticky);
+        let divisor = (clock_hz as u64)
+            .saturating_mul(bit_ns?)
+            .saturating_add(8_000_000_000)
+            / 16_000_000_000;
+        match divisor {
+            1..=0xFFFF => Some(divisor as u16),
+            _ => None,
+        }
+    }
+
+    /// Times the break and `0x55` sequence of [`autobaud_measure`](Self::autobaud_measure), returning one bit time.
+    fn measure_bit_ns(&self, timer: &mut impl FnMut() -> u64, deadline: u64) -> Option<u64> {
+        // The deadline is checked before each byte, so a line that never pauses still times out.
+        let mut arrival = || loop {
+            let now = timer();
+            if now >= deadline {
+                return None;
+            }
+            let lsr = self.uart.read_lsr();
+            if lsr.is_data_ready() {
+                self.uart.read_rbr_thr_dll();
+                return Some((now, lsr));
+            }
+        };
+        while !arrival()?.1.is_broken() {}
+        let mut edges = [0_u64; 5];
+        for edge in &mut edges {
+            *edge = arrival()?.0;
+        }
+        // A timer that wraps or goes backwards gives no measurement rather than a bogus one.
+        let total = edges[4].checked_sub(edges[0])?;
+        let mut even = true;
+        for pair in edges.windows(2) {
+            let gap = pair[1].checked_sub(pair[0])?;
+            even &= 4 * (4 * gap).abs_diff(total) <= total;
+        }
+        match even {
+            true => Some(total / 8),
+            false => None,
+        }
+    }
+
+    /// Writes each byte and reads its echo back into the same slot.
+    ///
+    /// This assumes a half-duplex peer that echoes every byte before the next one is sent.
+    /// Each byte is written, then its echo is awaited for at most `spin_limit` polls.
+    /// When the echo differs from the byte sent, `mismatch` decides whether to stop or overwrite.
+    /// An echo received with a parity, framing or break error, as checked by [`read_checked`](Self::read_checked),
+    /// is never taken as valid and always stops the transfer.
+    /// Returns the number of bytes whose echo was received and accepted. Bytes sent whose echo
+    /// was not accepted are still counted as sent when statistics are enabled.
+    pub fn transfer_in_place(
+        &mut self,
+        buf: &mut [u8],
+        mismatch: EchoMismatch,
+        spin_limit: u32,
+    ) -> usize {
+        let uart = &self.uart;
+        let strategy = self.write_strategy;
+        let (mut sent, mut received, mut count) = (0_usize, 0_usize, 0_usize);
+        for ch in buf {
+            if !spin_until(spin_limit, || strategy.is_ready(uart.read_lsr())) {
+                break;
+            }
+            sent += blocking_write(uart, &[*ch], 1, strategy);
+            let mut lsr = Lsr::default();
+            if !spin_until(spin_limit, || {
+                lsr = uart.read_lsr();
+                lsr.is_data_ready()
+            }) {
+                break;
+            }
+            let echo = uart.read_rbr_thr_dll().receiver_data();
+            received += 1;
+            match rx_error(lsr, self.ignore_rx_parity) {
+                None | Some(UartError::Overrun) => {}
+                Some(_) => break,
+            }
+            if echo != *ch {
+                match mismatch {
+                    EchoMismatch::Stop => break,
+                    EchoMismatch::Overwrite => *ch = echo,
+                }
+            }
+            count += 1;
+        }
+        self.count_tx(sent);
+        self.count_rx(received);
+        count
+    }
+
+    /// Writes all data, giving up when the transmitter makes no progress for `spin_limit` polls.
+    ///
+    /// The limit applies to each wait for room in the transmitter, so a long buffer on a healthy link
+    /// always completes, while a stalled link is detected after at most `spin_limit` polls.
+    /// Returns the part of `buf` that was not sent, which is empty on success.
+    pub fn write_all_timeout<'a>(&mut self, mut buf: &'a [u8], spin_limit: u32) -> &'a [u8] {
+        while !buf.is_empty() {
+            if !spin_until(spin_limit, || self.tx_ready()) {
+                break;
+            }
+            let len = self.write(buf);
+            buf = &buf[len..];
+        }
+        buf
+    }
+
+    /// Writes all data, reporting progress after each chunk.
+    ///
+    /// The data is sent in chunks of 16 bytes, the FIFO depth of a 16550A, waiting for room as needed.
+    /// After each chunk `on_chunk` is called with the number of bytes sent so far,
+    /// which lets long writes kick a watchdog or update a progress display.
+    /// The callback runs while this UART is mutably borrowed, so it cannot call back into it;
+    /// it must not access the same UART through another handle either, or the output interleaves.
+    pub fn write_with_progress(&mut self, buf: &[u8], mut on_chunk: impl FnMut(usize)) {
+        let mut sent = 0_usize;
+        for chunk in buf.chunks(FIFO_DEPTH) {
+            blocking_write_all(&self.uart, chunk, self.tx_burst, self.write_strategy);
+            self.count_tx(chunk.len());
+            sent += chunk.len();
+            on_chunk(sent);
+        }
+    }
+
+    /// Sends the payload followed by its checksum, waiting for room as needed.
+    ///
+    /// The checksum byte is computed with `algo` over the whole payload, see [`ChecksumAlgo::verify`]
+    /// for the receiving side. Like [`write_all`](embedded_io::Write::write_all), this returns once
+    /// the last byte is queued, not when it has been sent.
+    pub fn write_with_checksum(&mut self, payload: &[u8], algo: ChecksumAlgo) {
+        let checksum = algo.compute(payload);
+        blocking_write_all(&self.uart, payload, self.tx_burst, self.write_strategy);
+        blocking_write_all(&self.uart, &[checksum], 1, self.write_strategy);
+        self.count_tx(payload.len() + 1);
+    }
+
+    /// Reads a frame that fills the buffer and checks its trailing checksum byte.
+    ///
+    /// Exactly `buf.len()` bytes are read, waiting for each, so the frame length must be known;
+    /// the last byte is compared with the checksum of the others computed with `algo`,
+    /// as sent by [`write_with_checksum`](Self::write_with_checksum).
+    /// Returns the payload length, which is `buf.len() - 1`, or a [`ChecksumError`] carrying it
+    /// with the expected and received checksums; the payload is left in the buffer either way.
+    /// An empty buffer reads nothing and returns `Ok(0)`.
+    pub fn read_with_checksum(
+        &mut self,
+        buf: &mut [u8],
+        algo: ChecksumAlgo,
+    ) -> Result<usize, ChecksumError> {
+        for byte in buf.iter_mut() {
+            *byte = blocking_read_byte(&self.uart);
+        }
+        self.count_rx(buf.len());
+        let Some((&actual, payload)) = buf.split_last() else {
+            return Ok(0);
+        };
+        let expected = algo.compute(payload);
+        match expected == actual {
+            true => Ok(payload.len()),
+            false => Err(ChecksumError {
+                len: payload.len(),
+                expected,
+                actual,
+            }),
+        }
+    }
+
+    /// Sends `byte` `count` times, then waits until the last one has left the shift register.
+    ///
+    /// This is a debugging aid for hardware bring-up, giving a steady and predictable pattern on a scope.
+    pub fn send_pattern(&mut self, byte: u8, count: usize) {
+        self.send_repeated(&[byte; FIFO_DEPTH], count);
+    }
+
+    /// Sends `half_periods` bytes alternating between `0x00` and `0xFF`, then waits until the last one is sent.
+    ///
+    /// Each byte holds the line low or high for its data bits, so with the start and stop bits the TX line
+    /// shows a roughly square wave of two characters per period. This is a debugging aid for hardware bring-up.
+    pub fn send_square_wave(&mut self, half_periods: usize) {
+        const WAVE: [u8; FIFO_DEPTH] = {
+            let mut wave = [0x00; FIFO_DEPTH];
+            let mut i = 1;
+            while i < FIFO_DEPTH {
+                wave[i] = 0xFF;
+                i += 2;
+            }
+            wave
+        };
+        self.send_repeated(&WAVE, half_periods);
+    }
+
+    /// Sends `count` bytes, cycling through `chunk`, then flushes.
+    fn send_repeated(&mut self, chunk: &[u8; FIFO_DEPTH], count: usize) {
+        let mut left = count;
+        while left > 0 {
+            let len = left.min(FIFO_DEPTH);
+            blocking_write_all(
+                &self.uart,
+                &chunk[..len],
+                self.tx_burst,
+                self.write_strategy,
+            );
+            self.count_tx(len);
+            left -= len;
+        }
+        self.flush();
+    }
+
+    /// Writes a single byte only if the transmitter is completely idle.
+    ///
+    /// Idle means both the holding register or FIFO and the shift register are empty (TEMT),
+    /// so a keepalive byte never gets interleaved into a message still being sent.
+    /// Returns true if the byte was written.
+    pub fn write_if_idle(&mut self, byte: u8) -> bool {
+        if !self.uart.read_lsr().is_transmitter_empty() {
+            return false;
+        }
+        self.count_tx(blocking_write(&self.uart, &[byte], 1, self.write_strategy)) == 1
+    }
+
+    /// Flushes the UART transmitter.
+    ///
+    /// This function ensures all data has been transmitted before returning,
+    /// i.e. both the FIFO and the shift register are empty.
+    /// With a full FIFO this takes up to [`max_flush_time_us`](Self::max_flush_time_us),
+    /// roughly `fifo_depth * 10 / baud` seconds: about 5.6 ms for 64 bytes at 115200 baud.
+    /// It waits for the transmitter empty bit without a limit, so it never returns if the
+    /// transmitter is stalled, e.g. disabled by a vendor transmit enable register or
+    /// held off by hardware flow control; use [`flush_timeout`](Self::flush_timeout) in that case.
+    pub fn flush(&self) {
+        blocking_flush(&self.uart)
+    }
+
+    /// Flushes the UART transmitter, giving up after `spin_limit` polls.
+    ///
+    /// Returns true if the transmitter drained, or false if it is still sending or stalled.
+    /// The 16550 has no status bit for a stalled transmitter, e.g. one disabled by a vendor
+    /// transmit enable register or held off by hardware flow control, so the limit is the only way
+    /// to tell it apart from one still sending; choose it to cover a full FIFO at the current baud rate,
+    /// see [`max_flush_time_us`](Self::max_flush_time_us).
+    pub fn flush_timeout(&self, spin_limit: u32) -> bool {
+        spin_until(spin_limit, || self.uart.read_lsr().is_transmitter_empty())
+    }
+
+    /// Holds the line idle for `bit_times` bit times after the transmitter has drained.
+    ///
+    /// The transmitter empty bit is awaited first, so the gap starts after the stop bit
+    /// of the last character rather than when the FIFO hands it to the shift register.
+    /// The line then stays marking for `bit_times` bits at the current baud rate, timed with `delay`
+    /// from the divisor read back from the UART, which briefly sets DLAB; `clock` is the UART input clock.
+    /// Like [`flush`](Self::flush), this never returns if the transmitter is stalled.
+    ///
+    /// Modbus RTU delimits frames with at least 3.5 character times of silence,
+    /// i.e. 39 bit times with 11-bit characters, rounded up; above 19200 baud the specification
+    /// fixes the gap at 1.75 ms instead, which should be waited for with `delay` directly.
+    pub fn idle_gap(&self, clock: Hertz, bit_times: u32, delay: &mut impl DelayNs) {
+        let bit_ns = bit_duration_ns(clock.0, divisor(&self.uart));
+        self.flush();
+        delay.delay_ns(bit_ns.saturating_mul(bit_times));
+    }
+
+    /// Gets the longest time a [`flush`](Self::flush) can take at `baud`, in microseconds.
+    ///
+    /// This is the time to send a full transmit FIFO plus the character in the shift register,
+    /// with the character length given by the current line settings.
+    /// The FIFO depth comes from the FIFO settings this BlockingUart applied; after [`adopt`](Self::adopt)
+    /// FIFOs are assumed disabled. Hardware flow control can hold the transmitter off for longer.
+    /// Returns `u32::MAX` for a baud rate of 0.
+    pub fn max_flush_time_us(&self, baud: Baud) -> u32 {
+        if baud.0 == 0 {
+            return u32::MAX;
+        }
+        let depth = match (
+            self.fcr.is_fifo_enable_set(),
+            self.fcr.is_64byte_fifo_enabled(),
+        ) {
+            (true, true) => FIFO_DEPTH_64,
+            (true, false) => FIFO_DEPTH,
+            (false, _) => 1,
+        };
+        let lcr = self.uart.read_lcr();
+        let stop_bits = match lcr.stop_bits() {
+            StopBits::Bit1 => 1,
+            StopBits::Bits2OrBits1_5 => 2,
+        };
+        let char_bits =
+            1 + (lcr.word_length() as u64 + 5) + lcr.is_parity_enabled() as u64 + stop_bits;
+        let us = (depth as u64 + 1) * char_bits * 1_000_000 / baud.0 as u64;
+        us.min(u32::MAX as u64) as u32
+    }
+
+    /// Sets the Line Status Register bit that writes wait for, see [`WriteStrategy`].
+    pub fn set_write_strategy(&mut self, strategy: WriteStrategy) {
+        self.write_strategy = strategy;
+    }
+
+    /// Checks if the transmitter is ready for the next burst, according to the write strategy.
+    pub(crate) fn tx_ready(&self) -> bool {
+        self.write_strategy.is_ready(self.uart.read_lsr())
+    }
+
+    /// Checks if the transmitter can accept at least one more byte, according to the write strategy.
+    ///
+    /// With [`WriteStrategy::Thre`] this reads the THRE bit, which only says there is room in the holding
+    /// register or FIFO; with [`WriteStrategy::Temt`] it waits for TEMT as writes do, so a part with
+    /// an unreliable THRE is not overrun. Under THRE it does not mean that all queued data has been sent;
+    /// use [`flush`](Self::flush) for that.
+    pub fn tx_has_space(&self) -> bool {
+        self.tx_ready()
+    }
+
+    /// Gets the number of bytes waiting in the transmit FIFO, if it can be known.
+    ///
+    /// The classic 16550 has no transmit FIFO level register, only THRE. This returns `Some(0)`
+    /// when the holding register or FIFO is empty, and `Some(1)` when FIFOs are off and the holding register is full.
+    /// With FIFOs enabled and THRE clear, anything from one byte to a full FIFO may be queued, so this returns `None`.
+    /// Parts with a level register report the exact count there instead,
+    /// e.g. [`DwRegisterBlock::tx_fifo_level`](crate::DwRegisterBlock::tx_fifo_level) on DesignWare UARTs.
+    pub fn tx_fifo_level(&self) -> Option<usize> {
+        match self.uart.read_lsr().is_transmitter_fifo_empty() {
+            true => Some(0),
+            false if !self.is_fifo_enabled() => Some(1),
+            false => None,
+        }
+    }
+
+    /// Waits until fewer than `level` bytes are queued in the transmit FIFO.
+    ///
+    /// This lets a producer write its next burst as soon as there is room, without waiting
+    /// for the line to go idle as [`flush`](Self::flush) does.
+    /// The precision depends on the part: the classic 16550 only reports an empty holding register
+    /// or FIFO through THRE. Without FIFOs the holding register holds a single byte, so any `level`
+    /// of 2 or more is met at once; otherwise this waits for THRE. That is never too early,
+    /// but with FIFOs may wait longer than needed. On parts with a transmit FIFO level register use the exact wait,
+    /// e.g. [`DwRegisterBlock::wait_tx_below`](crate::DwRegisterBlock::wait_tx_below) on DesignWare UARTs.
+    /// As there, a `level` of 0 can never be met and is treated as 1, i.e. waiting for THRE.
+    pub fn wait_tx_below(&self, level: usize) {
+        if !self.is_fifo_enabled() && level >= 2 {
+            return;
+        }
+        while !self.uart.read_lsr().is_transmitter_fifo_empty() {
+            DefaultWait::wait();
+        }
+    }
+
+    /// Gets the identification of the highest priority pending interrupt.
+    ///
+    /// Reading the IIR acknowledges a pending transmitter holding register empty interrupt,
+    /// as does [`raw_iir`](Self::raw_iir), so only one of them should be read per interrupt.
+    /// A [`CharacterTimeout`](InterruptId::CharacterTimeout) is raised when bytes sit in the
+    /// receiver FIFO below the trigger level and no new character arrives for four character times;
+    /// it is cleared by reading the receiver buffer, so the handler should drain the FIFO completely.
+    ///
+    /// # Example
+    ///
+    /// Reading variable-length frames, where the character timeout marks the end of a frame:
+    ///
+    /// ```
+    /// use uart16550::{BlockingUart, InterruptId, RegisterBlock};
+    ///
+    /// fn on_uart_interrupt(
+    ///     uart: &BlockingUart<&'static RegisterBlock>,
+    ///     frame: &mut [u8],
+    ///     len: &mut usize,
+    ///     on_frame: impl FnOnce(&[u8]),
+    /// ) {
+    ///     match uart.interrupt_id() {
+    ///         InterruptId::ReceivedDataAvailable => {
+    ///             *len += uart.read(&mut frame[*len..]);
+    ///         }
+    ///         InterruptId::CharacterTimeout => {
+    ///             // The line went idle: drain the partial FIFO and finish the frame.
+    ///             *len += uart.read(&mut frame[*len..]);
+    ///             on_frame(&frame[..*len]);
+    ///             *len = 0;
+    ///         }
+    ///         _ => {}
+    ///     }
+    /// }
+    /// ```
+    pub fn interrupt_id(&self) -> InterruptId {
+        self.uart.read_iir_fcr().interrupt_id()
+    }
+
+    /// Gets the FIFO status reported by the IIR.
+    ///
+    /// This is the authoritative check that FIFOs are really on: the FCR is write-only,
+    /// and a 16450 or an original 16550 accepts the enable bit without working FIFOs.
+    /// [`new`](Self::new) and [`enable_fifo`](Self::enable_fifo) check it the same way before allowing bursts.
+    /// Like [`interrupt_id`](Self::interrupt_id), reading the IIR acknowledges a pending
+    /// transmitter holding register empty interrupt.
+    pub fn fifo_status(&self) -> FifoStatus {
+        self.uart.read_iir_fcr().fifo_status()
+    }
+
+    /// Gets the raw value of the Interrupt Identification Register.
+    ///
+    /// This is an escape hatch for custom interrupt dispatch and for logging unexpected interrupts:
+    /// bits 0 to 3 hold the interrupt identification decoded by [`interrupt_id`](Self::interrupt_id),
+    /// bits 6 and 7 the FIFO status, and vendor parts may report more in the other bits.
+    /// Like [`interrupt_id`](Self::interrupt_id), reading the IIR acknowledges a pending
+    /// transmitter holding register empty interrupt.
+    pub fn raw_iir(&self) -> u8 {
+        self.uart.read_iir_fcr().bits() as u8
+    }
+
+    /// Gets the enabled interrupt sources.
+    ///
+    /// The Interrupt Enable Register shares its address with the divisor latch high byte.
+    /// If DLAB happens to be set, it is cleared for the read and LCR is restored afterwards,
+    /// so the divisor is never mistaken for the interrupt enables.
+    pub fn interrupt_mask(&self) -> InterruptMask {
+        interrupt_mask(&self.uart)
+    }
+
+    /// Drives the OUT1 output of the Modem Control Register.
+    ///
+    /// What OUT1 is connected to is board-specific, e.g. a transceiver enable or an LED,
+    /// and on many SoC UARTs it reaches no pin at all. The other MCR bits are left unchanged.
+    pub fn set_out1(&mut self, val: bool) {
+        let mcr = self.uart.read_mcr().set_out1(val);
+        unsafe {
+            self.uart.write_mcr(mcr);
+        }
+        self.out1 = val;
+    }
+
+    /// Gets the last level set on OUT1.
+    ///
+    /// This is kept in software, so it is correct even on parts whose MCR does not read back.
+    /// It starts from the MCR value read by [`new`](Self::new), or false after [`adopt`](Self::adopt).
+    pub fn out1(&self) -> bool {
+        self.out1
+    }
+
+    /// Gets the parity mode from the LCR.
+    ///
+    /// This is the high-level view, in which stick parity shows up as [`ParityMode::High`] or [`ParityMode::Low`].
+    pub fn parity_mode(&self) -> ParityMode {
+        parity_mode(&self.uart)
+    }
+
+    /// Checks if stick parity is enabled in the LCR.
+    ///
+    /// With stick parity the parity bit is constant instead of computed; see [`base_parity`](Self::base_parity).
+    /// The bit is reported as set even while parity itself is disabled, in which case it has no effect.
+    pub fn is_stick_parity(&self) -> bool {
+        self.uart.read_lcr().is_stick_parity_enabled()
+    }
+
+    /// Starts or stops sending a break, by setting the break control bit of the LCR.
+    ///
+    /// While set, the transmitter output is held low (spacing), e.g. for the break field of a LIN header.
+    /// A character still being shifted out is corrupted, so [`flush`](Self::flush) first.
+    /// Only the break bit is changed; DLAB is not touched.
+    pub fn set_break(&mut self, enable: bool) {
+        let lcr = self.uart.read_lcr();
+        let lcr = match enable {
+            true => lcr.enable_break_control(),
+            false => lcr.disable_break_control(),
+        };
+        unsafe {
+            self.uart.write_lcr(lcr);
+        }
+        write_volatile_barrier();
+    }
+
+    /// Checks if a break is being sent, by reading the break control bit of the LCR back.
+    ///
+    /// This is a plain LCR read that never toggles DLAB, so it can check that the break set with
+    /// [`set_break`](Self::set_break) really took effect, e.g. on parts that drop LCR writes while busy.
+    pub fn is_break_set(&self) -> bool {
+        self.uart.read_lcr().is_break_control_enabled()
+    }
+
+    /// Gets the even parity select bit of the LCR.
+    ///
+    /// Without stick parity this selects even or odd parity. With stick parity it inverts the
+    /// constant parity bit: [`Parity::Odd`] sends a 1 (mark) and [`Parity::Even`] a 0 (space).
+    pub fn base_parity(&self) -> Parity {
+        self.uart.read_lcr().parity()
+    }
+
+    /// Gets a snapshot of the full configuration, for diagnostics.
+    ///
+    /// The FIFO fields come from the shadow of the write-only FCR, see [`ExtendedConfig`];
+    /// after [`adopt`](Self::adopt) they report FIFOs disabled, whatever the hardware state.
+    /// Reading the divisor briefly sets DLAB, as for [`config`](Self::config).
+    pub fn extended_config(&self) -> ExtendedConfig {
+        let mcr = self.uart.read_mcr();
+        ExtendedConfig {
+            config: crate::config(&self.uart),
+            fifo_enabled: self.fcr.is_fifo_enable_set(),
+            fifo_64byte: self.fcr.is_64byte_fifo_enabled(),
+            receiver_trigger_level: self.fcr.receiver_trigger_level(),
+            auto_flow_control: mcr.is_auto_flow_control_enabled(),
+            interrupt_mask: interrupt_mask(&self.uart),
+            data_terminal_ready: mcr.data_terminal_ready(),
+            request_to_send: mcr.request_to_send(),
+            out1: mcr.out1(),
+            out2: mcr.out2(),
+            loop_back: mcr.is_loop_back_enabled(),
+        }
+    }
+
+    /// Checks if any character in the receiver FIFO has a parity, framing or break error.
+    ///
+    /// This reads the FIFO error bit of the Line Status Register, a quick check before draining the FIFO:
+    /// when it is clear, [`read`](Self::read) can be used instead of [`read_checked`](Self::read_checked).
+    /// The bit is only set in FIFO mode, so it is always false when FIFOs are disabled.
+    /// Like any LSR read this clears the error bits describing the character at the front of the FIFO,
+    /// so when it returns true that character's error is lost to a following [`read_checked`](Self::read_checked);
+    /// use [`line_status`](Self::line_status) to get both at once.
+    pub fn has_fifo_error(&self) -> bool {
+        self.uart.read_lsr().is_receiver_fifo_error()
+    }
+
+    /// Enables exactly the interrupt sources in `mask`, disabling the others.
+    ///
+    /// Like [`interrupt_mask`](Self::interrupt_mask), this is safe to call while DLAB is set.
+    ///
+    /// # OUT2
+    ///
+    /// On PC-compatible boards the UART interrupt output reaches the interrupt controller
+    /// through a gate controlled by the OUT2 bit of the Modem Control Register.
+    /// Until OUT2 is set, interrupts enabled in the IER never arrive,
+    /// which is the most common reason for a first interrupt driver to stay silent.
+    /// Pass `set_out2 = true` on such boards to set OUT2 as well.
+    /// Most SoC UARTs wire the interrupt directly; pass `false` there,
+    /// which leaves OUT2 unchanged, e.g. when it drives a board signal instead.
+    pub fn enable_interrupts(&mut self, mask: InterruptMask, set_out2: bool) {
+        set_interrupt_mask(&self.uart, mask);
+        if set_out2 {
+            let mcr = self.uart.read_mcr().set_out2(true);
+            unsafe {
+                self.uart.write_mcr(mcr);
+            }
+        }
+    }
+
+    /// Reads the Line Status Register.
+    ///
+    /// This clears the overrun, parity, framing and break bits in the hardware.
+    /// A framing error reported together with a break is suppressed, see [`LineStatus`].
+    pub fn line_status(&self) -> LineStatus {
+        LineStatus::from(self.uart.read_lsr())
+    }
+
+    /// Gets the receiver errors seen since creation or the last [`clear_sticky_errors`](Self::clear_sticky_errors).
+    ///
+    /// Any LSR read clears the error bits on a classic 16550, so there is no way to look at them
+    /// without acknowledging them. Instead, the errors of every LSR read made by this wrapper
+    /// are accumulated here, whether by [`line_status`](Self::line_status), a read or a write.
+    /// Only the error fields of the returned value are used; the others are always false.
+    /// Reads made through [`registers`](Self::registers) are not seen.
+    pub fn sticky_errors(&self) -> LineStatus {
+        self.uart.sticky.get()
+    }
+
+    /// Clears the receiver errors accumulated for [`sticky_errors`](Self::sticky_errors).
+    pub fn clear_sticky_errors(&self) {
+        self.uart.sticky.set(LineStatus::default());
+    }
+
+    /// Classifies the receive line by sampling the Line Status Register `samples` times.
+    ///
+    /// This helps report a disconnected cable: an unconnected RX input floats, and usually reads
+    /// as a stream of framing errors, or as a break when it is pulled low.
+    /// Received bytes are discarded, so that later characters and their errors are seen too.
+    /// The breaks and the other receiver errors are counted separately over all samples, and the rules are,
+    /// in order:
+    ///
+    /// - [`LineHealth::Break`] if at least `error_threshold` breaks were seen.
+    /// - [`LineHealth::Noisy`] if at least `error_threshold` parity, framing or overrun errors were seen.
+    /// - [`LineHealth::Active`] if any byte was received.
+    /// - [`LineHealth::Idle`] otherwise.
+    ///
+    /// An `error_threshold` of 0 is treated as 1. A break or error spans one character time,
+    /// so the samples should cover several characters at the configured baud rate for a stable result,
+    /// see [`read_frame`](Self::read_frame) for how iterations relate to time.
+    /// This never waits beyond the `samples` reads, and the errors seen also accumulate in [`sticky_errors`](Self::sticky_errors).
+    pub fn line_health(&self, samples: u32, error_threshold: u32) -> LineHealth {
+        let threshold = error_threshold.max(1);
+        let mut breaks = 0_u32;
+        let mut errors = 0_u32;
+        let mut received = false;
+        for _ in 0..samples {
+            let lsr = self.uart.read_lsr();
+            match UartError::from_line_status(lsr) {
+                Some(UartError::Break) => breaks += 1,
+                Some(_) => errors += 1,
+                None => {}
+            }
+            if lsr.is_data_ready() {
+                received = true;
+                self.uart.read_rbr_thr_dll();
+            }
+        }
+        if breaks >= threshold {
+            LineHealth::Break
+        } else if errors >= threshold {
+            LineHealth::Noisy
+        } else if received {
+            LineHealth::Active
+        } else {
+            LineHealth::Idle
+        }
+    }
+
+    /// Reads the Modem Status Register, acknowledging a modem status interrupt.
+    ///
+    /// The MSR is read exactly once. That read clears the delta bits and the pending
+    /// [`ModemStatus`](InterruptId::ModemStatus) interrupt, and the returned snapshot holds
+    /// both the line states and the delta bits captured by it.
+    /// A modem status interrupt stays asserted until the MSR is read, so the handler must call this.
+    pub fn take_modem_status_change(&self) -> ModemStatus {
+        ModemStatus::from(self.uart.read_msr())
+    }
+
+    /// Checks if a UART responds at this address, using only the scratch register.
+    ///
+    /// Two complementary patterns are written to the scratch register and must read back unchanged,
+    /// which an unmapped region reading a constant `0x00` or `0xFF` fails.
+    /// The original scratch value is restored. No other register is touched,
+    /// so this is safe on a line that is in use; see [`health_check`](Self::health_check) for a deeper test.
+    /// Parts without a scratch register, such as the original 8250, are never detected.
+    pub fn is_present(&self) -> bool {
+        let scr = self.uart.read_scr();
+        let present = [0x55, 0xAA].iter().all(|&pattern| {
+            unsafe {
+                self.uart.write_scr(scr.set_scratchpad(pattern));
+            }
+            self.uart.read_scr().scratchpad() == pattern
+        });
+        unsafe {
+            self.uart.write_scr(scr);
+        }
+        present
+    }
+
+    /// Probes the features of the UART, restoring its state afterwards.
+    ///
+    /// The scratch register tells an 8250 apart; FIFOs are then enabled in 64-byte mode, with DLAB set
+    /// as the 16750 requires, and the FIFO status and 64-byte bits of the IIR tell which FIFOs work.
+    /// Auto flow control is found by setting its MCR bit, which reads back as 0 on parts without it.
+    /// The scratch register, MCR and FCR are restored afterwards, the FCR from its shadow.
+    /// Toggling the FIFO enable clears the FIFOs on most parts, so probe before the line is in use.
+    /// Reading the IIR acknowledges a pending transmitter holding register empty interrupt.
+    pub fn capabilities(&mut self) -> Capabilities {
+        let mut caps = Capabilities {
+            kind: UartKind::Uart8250,
+            fifo_depth: 1,
+            has_64byte_fifo: false,
+            has_auto_flow: false,
+            has_dlf: false,
+        };
+        if !self.is_present() {
+            return caps;
+        }
+        self.write_fcr_with_dlab(
+            IirFcr::default()
+                .enable_fifo()
+                .set_receiver_trigger_level(ReceiverTriggerLevel::FifoFullSub2)
+                .enable_64byte_fifo(),
+        );
+        let iir = self.uart.read_iir_fcr();
+        self.write_fcr_with_dlab(self.fcr);
+        (caps.kind, caps.fifo_depth) = match (iir.fifo_status(), iir.is_64byte_fifo_enabled()) {
+            (FifoStatus::Enabled, true) => (UartKind::Uart16750, FIFO_DEPTH_64),
+            (FifoStatus::Enabled, false) => (UartKind::Uart16550A, FIFO_DEPTH),
+            (FifoStatus::EnabledNonFunctioning, _) => (UartKind::Uart16550, 1),
+            (FifoStatus::Disabled, _) => (UartKind::Uart16450, 1),
+        };
+        caps.has_64byte_fifo = caps.kind == UartKind::Uart16750;
+
+        let mcr = self.uart.read_mcr();
+        unsafe {
+            self.uart.write_mcr(mcr.enable_auto_flow_control());
+        }
+        caps.has_auto_flow = self.uart.read_mcr().is_auto_flow_control_enabled();
+        unsafe {
+            self.uart.write_mcr(mcr);
+        }
+        caps
+    }
+
+    /// Checks if the UART responds, without waiting more than `spin_limit` polls at each step.
+    ///
+    /// The UART must pass [`is_present`](Self::is_present) first,
+    /// then one byte is sent in loopback mode and must be received back unchanged.
+    /// Any received data still pending is discarded by the loopback test,
+    /// and the modem control and scratch registers are restored afterwards.
+    /// Returns false if the UART looks wedged; [`recover`](Self::recover) may bring it back.
+    pub fn health_check(&mut self, spin_limit: u32) -> bool {
+        self.is_present() && self.loop_back(0xA5, spin_limit)
+    }
+
+    /// Checks that a byte sent at the configured baud rate is received back intact in loopback mode.
+    ///
+    /// `0x55` is sent, as its alternating bits are the most sensitive to bit timing,
+    /// and must be received without parity, framing or break error; each wait is bounded by `spin_limit` polls.
+    /// Any received data still pending is discarded, and the modem control register is restored afterwards.
+    ///
+    /// In loopback mode the transmitter and receiver share the baud generator,
+    /// so this catches a generator that does not run or a corrupted line setting,
+    /// but not a clock frequency that differs from the one assumed when computing the divisor;
+    /// that needs a peer on the wire, see [`autobaud_scan`](Self::autobaud_scan), or a timer, see [`estimate_clock`](Self::estimate_clock).
+    pub fn verify_baud(&mut self, spin_limit: u32) -> bool {
+        self.loop_back(0x55, spin_limit)
+    }
+
+    /// Measures the bit error rate in loopback mode with `iterations` pseudo-random bytes.
+    ///
+    /// Each byte is sent and awaited before the next one, every wait bounded by `spin_limit` polls;
+    /// a byte that does not arrive in time counts as lost. The bytes come from a xorshift generator
+    /// started from `seed`, so a run can be reproduced; a `seed` of 0 is replaced by a fixed non-zero value.
+    /// Bytes received with a parity, framing or break error count as errors even if their data matches.
+    /// Any received data still pending is discarded before and after, and the modem control register
+    /// and sticky errors are restored afterwards. The bytes are not counted in the statistics.
+    ///
+    /// As for [`verify_baud`](Self::verify_baud), the transmitter and receiver share the baud generator in loopback,
+    /// so this qualifies the UART and its settings, but not a mismatch with the clock of a peer.
+    pub fn ber_test(&mut self, iterations: usize, seed: u32, spin_limit: u32) -> BerResult {
+        let mcr = self.uart.read_mcr();
+        let sticky = self.uart.sticky.get();
+        unsafe {
+            self.uart.write_mcr(mcr.enable_loop_back());
+        }
+        self.discard_received();
+        let mut state = match seed {
+            0 => 0x2545_F491,
+            seed => seed,
+        };
+        let mut result = BerResult::default();
+        for _ in 0..iterations {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let byte = state as u8;
+            let mut lsr = Lsr::default();
+            let received = spin_until(spin_limit, || self.tx_ready())
+                && blocking_write(&self.uart, &[byte], 1, self.write_strategy) == 1
+                && spin_until(spin_limit, || {
+                    lsr = self.uart.read_lsr();
+                    lsr.is_data_ready()
+                });
+            let bit_errors = match received {
+                true => (self.uart.read_rbr_thr_dll().receiver_data() ^ byte).count_ones(),
+                false => 8,
+            };
+            result.bytes += 1;
+            if bit_errors > 0 || UartError::from_line_status(lsr).is_some() {
+                result.errors += 1;
+            }
+            result.bit_errors += bit_errors as u64;
+        }
+        self.discard_received();
+        unsafe {
+            self.uart.write_mcr(mcr);
+        }
+        self.uart.sticky.set(sticky);
+        result
+    }
+
+    /// Estimates the UART input clock by timing a frame in loopback mode.
+    ///
+    /// Two bytes are sent back to back in loopback mode, and `timer` is read when each is received;
+    /// the difference is one frame time at the programmed divisor, whatever the latency before the first byte.
+    /// The clock is then `16 * divisor * frame_bits / frame_time`, see [`Config::frame_duration_ns`].
+    /// `timer` must be a monotonic tick counter running at `timer_hz`.
+    /// Any received data still pending is discarded, and the modem control register is restored afterwards.
+    /// Returns `None` if a wait exceeds `spin_limit` polls, or if no tick elapsed.
+    ///
+    /// The accuracy is limited by the poll loop and the timer: each reception is seen up to one poll late,
+    /// and the timer resolution adds one tick, so the error is about `(t_poll + 1 / timer_hz) / frame_time`.
+    /// A large divisor, i.e. a low baud rate, gives the longest frame and the best estimate.
+    /// That is usually enough to tell which of the common UART clocks a board uses.
+    pub fn estimate_clock(
+        &mut self,
+        timer_hz: Hertz,
+        mut timer: impl FnMut() -> u64,
+        spin_limit: u32,
+    ) -> Option<Hertz> {
+        let config = crate::config(&self.uart);
+        let mcr = self.uart.read_mcr();
+        unsafe {
+            self.uart.write_mcr(mcr.enable_loop_back());
+        }
+        self.discard_received();
+        let tx_ready = || self.tx_ready();
+        let data_ready = || self.uart.read_lsr().is_data_ready();
+        let mut ticks = [0_u64; 2];
+        let mut ok = true;
+        for _ in 0..2 {
+            ok = ok
+                && spin_until(spin_limit, tx_ready)
+                && blocking_write(&self.uart, &[0x55], 1, self.write_strategy) == 1;
+        }
+        for tick in &mut ticks {
+            ok = ok && spin_until(spin_limit, data_ready);
+            *tick = timer();
+            self.uart.read_rbr_thr_dll();
+        }
+        unsafe {
+            self.uart.write_mcr(mcr);
+        }
+        let elapsed = ticks[1].checked_sub(ticks[0]).filter(|&t| t > 0);
+        match (ok, elapsed, config.divisor) {
+            (true, Some(elapsed), Some(divisor)) => {
+                let half_bits = config.frame_half_bits() as u64;
+                let clock = 8 * divisor as u64 * half_bits * timer_hz.0 as u64 / elapsed;
+                Some(Hertz(clock.min(u32::MAX as u64) as u32))
+            }
+            _ => None,
+        }
+    }
+
+    /// Sends one byte in loopback mode and checks it is received back without error.
+    fn loop_back(&self, byte: u8, spin_limit: u32) -> bool {
+        let mcr = self.uart.read_mcr();
+        unsafe {
+            self.uart.write_mcr(mcr.enable_loop_back());
+        }
+        self.discard_received();
+        let mut lsr = Lsr::default();
+        let ok = spin_until(spin_limit, || self.tx_ready())
+            && blocking_write(&self.uart, &[byte], 1, self.write_strategy) == 1
+            && spin_until(spin_limit, || {
+                lsr = self.uart.read_lsr();
+                lsr.is_data_ready()
+            })
+            && UartError::from_line_status(lsr).is_none()
+            && self.uart.read_rbr_thr_dll().receiver_data() == byte;
+        unsafe {
+            self.uart.write_mcr(mcr);
+        }
+        ok
+    }
+
+    /// Resets the UART to unwedge it, keeping the configured baud rate and line settings.
+    ///
+    /// Interrupts are disabled, both FIFOs are cleared, pending data and line status are discarded,
+    /// then the configuration, FIFO settings and interrupt enables are written again.
+    /// Data waiting in the transmitter is lost. This never waits on the hardware, so it cannot hang.
+    /// A DLAB left set, e.g. by an interrupted divisor update, is cleared first.
+    pub fn recover(&mut self) {
+        let lcr = self.uart.read_lcr().disable_divisor_latch_access();
+        unsafe {
+            self.uart.write_lcr(lcr);
+        }
+        write_volatile_barrier();
+        let config = crate::config(&self.uart);
+        let ier = self.uart.read_ier_dlh();
+        let disabled = ier
+            .disable_modem_status_interrupt()
+            .disable_receiver_line_status_interrupt()
+            .disable_received_data_available_interrupt()
+            .disable_transmitter_empty_interrupt();
+        unsafe {
+            self.uart.write_ier_dlh(disabled);
+            self.uart.write_iir_fcr(
+                IirFcr::default()
+                    .disable_fifo()
+                    .reset_receiver_fifo()
+                    .reset_transmitter_fifo(),
+            );
+        }
+        write_volatile_barrier();
+        self.discard_received();
+        self.uart.read_msr();
+
+        set_config(&self.uart, &config);
+        // Through DLAB, so the 64-byte mode of a 16750 is restored too.
+        self.write_fcr_with_dlab(self.fcr.reset_receiver_fifo().reset_transmitter_fifo());
+        unsafe {
+            self.uart.write_ier_dlh(ier);
+        }
+    }
+
+    /// Discards received data, reading at most a full FIFO's worth so a stuck data ready bit cannot hang.
+    ///
+    /// This also clears the error bits of the Line Status Register.
+    fn discard_received(&self) {
+        for _ in 0..64 {
+            if !self.uart.read_lsr().is_data_ready() {
+                break;
+            }
+            self.uart.read_rbr_thr_dll();
+        }
+    }
+
+    /// Gets the number of bytes written to the transmitter since creation or the last [`reset_counts`](Self::reset_counts).
+    ///
+    /// Together with a timer this gives the effective throughput of the link.
+    /// Bytes sent by self-tests such as [`health_check`](Self::health_check) are not counted.
+    #[cfg(feature = "stats")]
+    pub fn tx_count(&self) -> u64 {
+        self.tx_count.get()
+    }
+
+    /// Gets the number of bytes taken from the receiver since creation or the last [`reset_counts`](Self::reset_counts).
+    ///
+    /// Bytes discarded because of receiver errors are not counted.
+    #[cfg(feature = "stats")]
+    pub fn rx_count(&self) -> u64 {
+        self.rx_count.get()
+    }
+
+    /// Resets the transmitted and received byte counters to zero.
+    #[cfg(feature = "stats")]
+    pub fn reset_counts(&self) {
+        self.tx_count.set(0);
+        self.rx_count.set(0);
+    }
+
+    /// Adds to the transmitted byte counter when statistics are enabled, returning `len`.
+    #[inline(always)]
+    pub(crate) fn count_tx(&self, len: usize) -> usize {
+        #[cfg(feature = "stats")]
+        self.tx_count
+            .set(self.tx_count.get().wrapping_add(len as u64));
+        len
+    }
+
+    /// Checks if FIFOs were enabled and the IIR confirmed them, so writes go out in bursts.
+    ///
+    /// The shadow of the FCR alone is not enough: a 16450 ignores the FCR write.
+    #[inline(always)]
+    pub(crate) fn is_fifo_enabled(&self) -> bool {
+        self.tx_burst > 1
+    }
+
+    /// Gets the number of bytes written per THRE assertion.
+    #[inline(always)]
+    pub(crate) fn tx_burst(&self) -> usize {
+        self.tx_burst
+    }
+
+    /// Adds to the received byte counter when statistics are enabled, returning `len`.
+    #[inline(always)]
+    pub(crate) fn count_rx(&self, len: usize) -> usize {
+        #[cfg(feature = "stats")]
+        self.rx_count
+            .set(self.rx_count.get().wrapping_add(len as u64));
+        len
+    }
+
+    /// Gets a reference to the register accessor.
+    ///
+    /// This is an escape hatch for register accesses not covered by this wrapper.
+    /// Raw writes through it bypass any state the wrapper keeps in software,
+    /// so changing registers behind its back can leave that state out of sync with the hardware.
+    #[inline]
+    pub fn registers(&self) -> &UART {
+        &self.uart.regs
+    }
+}
+
+impl BlockingUart<MmioRegisters> {
+    /// Creates a new BlockingUart from devicetree-style parameters, see [`UartParams`].
+    ///
+    /// The registers at `base` are reached with the stride and access width of `params`.
+    /// If [`UartParams::current_speed`] is set, the divisor is computed from it and the clock,
+    /// replacing the one in `config`; otherwise `config` is applied as is, as by [`new`](Self::new).
+    /// FIFOs are enabled, which parts without them ignore.
+    /// Returns the error of [`UartParams::validate`] without touching any register if the parameters are invalid.
+    ///
+    /// # Safety
+    ///
+    /// `base` must be the address of the UART described by `params`, see [`MmioRegisters::new`].
+    pub unsafe fn from_params(
+        base: usize,
+        params: UartParams,
+        config: Config,
+    ) -> Result<Self, ConfigError> {
+        let width = params.validate()?;
+        let config = match params.current_speed {
+            Some(baud) => config.set_baud_rate(params.clock_frequency, baud),
+            None => config,
+        };
+        let uart = unsafe { MmioRegisters::new(base, params.reg_shift, width) };
+        Ok(BlockingUart::new(uart, config, true))
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>> BlockingUart<UART> {
+    /// Gets a reference to the underlying register block.
+    ///
+    /// This is an escape hatch for register accesses not covered by this wrapper,
+    /// with the same caveats as [`registers`](Self::registers).
+    #[inline]
+    pub fn register_block(&self) -> &RegisterBlock {
+        &self.uart.regs
+    }
+}
+
+/// A guard that restores the original UART configuration when dropped.
+///
+/// Created by [`BlockingUart::temp_config`].
+pub struct ConfigGuard<'a, UART: UartRegisters> {
+    uart: &'a mut BlockingUart<UART>,
+    original: Config,
+}
+
+impl<UART: UartRegisters> Deref for ConfigGuard<'_, UART> {
+    type Target = BlockingUart<UART>;
+
+    fn deref(&self) -> &Self::Target {
+        self.uart
+    }
+}
+
+impl<UART: UartRegisters> DerefMut for ConfigGuard<'_, UART> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.uart
+    }
+}
+
+impl<UART: UartRegisters> Drop for ConfigGuard<'_, UART> {
+    fn drop(&mut self) {
+        self.uart.flush();
+        set_config(&self.uart.uart, &self.original);
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>> AsRef<RegisterBlock> for BlockingUart<UART> {
+    #[inline]
+    fn as_ref(&self) -> &RegisterBlock {
+        &self.uart.regs
+    }
+}
+
+impl<UART: UartRegisters> ErrorType for BlockingUart<UART> {
+    type Error = core::convert::Infallible;
+}
+
+impl<UART: UartRegisters> embedded_io::Read for BlockingUart<UART> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(BlockingUart::read(self, buf))
+    }
+}
+
+impl<UART: UartRegisters> embedded_io::Write for BlockingUart<UART> {
+    /// Writes data, waiting until the transmitter accepts at least one byte unless `buf` is empty.
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        loop {
+            let len = BlockingUart::write(self, buf);
+            if len > 0 || buf.is_empty() {
+                return Ok(len);
+            }
+            DefaultWait::wait();
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        blocking_flush(&self.uart);
+        Ok(())
+    }
+}
+
+impl<UART: UartRegisters> core::fmt::Write for BlockingUart<UART> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        blocking_write_all(&self.uart, s.as_bytes(), self.tx_burst, self.write_strategy);
+        self.count_tx(s.len());
+        Ok(())
+    }
+}
+
+impl<UART: UartRegisters> embedded_io::ReadReady for BlockingUart<UART> {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(read_ready(&self.uart))
+    }
+}
+
+impl<UART: UartRegisters> embedded_io::WriteReady for BlockingUart<UART> {
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.tx_ready())
+    }
+}
+
+impl<UART: UartRegisters> embedded_hal_nb::serial::ErrorType for BlockingUart<UART> {
+    type Error = core::convert::Infallible;
+}
+
+impl<UART: UartRegisters> embedded_hal_nb::serial::Read for BlockingUart<UART> {
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        let mut buf = [0];
+        let len = BlockingUart::read(self, &mut buf);
+        match len {
+            0 => Err(nb::Error::WouldBlock),
+            _ => Ok(buf[0]),
+        }
+    }
+}
+
+impl<UART: UartRegisters> embedded_hal_nb::serial::Write for BlockingUart<UART> {
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        let len = BlockingUart::write(self, &[word]);
+        match len {
+            0 => Err(nb::Error::WouldBlock),
+            _ => Ok(()),
+        }
+    }
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        match self.uart.read_lsr().is_transmitter_empty() {
+            true => Ok(()),
+            false => Err(nb::Error::WouldBlock),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockUart, mock_uart, set_lsr};
+    use crate::{IerDlh, Lcr, ParityMode, StopBits, WordLength};
+
+    #[test]
+    fn blocking_uart_temp_config() {
+        let mock = mock_uart();
+        let original = Config::new().set_divisor(0x0060);
+        let temp = Config::new()
+            .set_divisor(0x0001)
+            .set_parity_mode(ParityMode::Even)
+            .set_stop_bits(StopBits::Bits2OrBits1_5)
+            .set_word_length(WordLength::Bits7);
+        let mut uart = BlockingUart::new(&mock, original, false);
+
+        // Test the temporary configuration is applied inside the closure
+        let applied = uart.with_temp_config(temp, |uart| BlockingUart::config(*uart.registers()));
+        assert_eq!(applied, temp);
+
+        // Test the original configuration is restored afterwards
+        assert_eq!(BlockingUart::config(&mock), original);
+        assert!(!mock.lcr.read().is_divisor_latch_access_enabled());
+    }
+
+    #[test]
+    fn blocking_uart_custom_registers() {
+        let mock = MockUart::new();
+        let config = Config::new()
+            .set_divisor(0x1234)
+            .set_word_length(WordLength::Bits7);
+
+        // Test a backend with the divisor latch behind DLAB keeps it apart from the IER
+        let mut uart = BlockingUart::new(&mock, config, false);
+        uart.enable_interrupts(
+            InterruptMask {
+                modem_status: true,
+                ..InterruptMask::default()
+            },
+            false,
+        );
+        assert_eq!(BlockingUart::config(&mock), config);
+        assert!(uart.interrupt_mask().modem_status);
+        assert!(!mock.regs.lcr.read().is_divisor_latch_access_enabled());
+    }
+
+    #[test]
+    fn blocking_uart_estimate_clock() {
+        let mock = MockUart::new();
+        let mut uart = BlockingUart::new(&mock, Config::new().set_divisor(1), false);
+        set_lsr(&mock.regs, 0x61);
+
+        // Test one 8N1 frame of 86.8 us means a 1.8432 MHz clock
+        let mut now = 0;
+        let timer = || {
+            now += 86_805;
+            now
+        };
+        assert_eq!(
+            uart.estimate_clock(Hertz(1_000_000_000), timer, 16),
+            Some(Hertz(1_843_211))
+        );
+        assert!(!mock.regs.mcr.read().is_loop_back_enabled());
+
+        // Test a timer that does not advance
+        assert_eq!(uart.estimate_clock(Hertz(1_000_000_000), || 0, 16), None);
+    }
+
+    #[test]
+    fn blocking_uart_read_below_trigger_level() {
+        let mock = MockUart::new();
+        let mut uart = BlockingUart::new(&mock, Config::new(), true);
+        uart.set_receiver_trigger_level(ReceiverTriggerLevel::FifoFullSub2);
+
+        // Test bytes below the trigger level are read one by one
+        mock.set_rx_pending(3);
+        assert_eq!(uart.read(&mut [0; 16]), 3);
+        assert_eq!(uart.read(&mut [0; 16]), 0);
+
+        // Test draining stops when the receiver is empty or the buffer is full
+        mock.set_rx_pending(5);
+        assert_eq!(uart.drain_available(&mut [0; 4]), 4);
+        assert_eq!(uart.drain_available(&mut [0; 4]), 1);
+
+        // Test an uninitialized read only returns the bytes received
+        mock.set_rx_pending(2);
+        let mut buf = [MaybeUninit::uninit(); 8];
+        assert_eq!(uart.read_uninit(&mut buf).len(), 2);
+        assert!(uart.read_uninit(&mut buf).is_empty());
+    }
+
+    #[test]
+    fn blocking_uart_write_contract() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+
+        // Test a full transmitter accepts nothing, without blocking
+        set_lsr(&mock, 0x00);
+        assert_eq!(uart.write(&[1, 2]), 0);
+        assert_eq!(embedded_io::Write::write(&mut uart, &[]), Ok(0));
+
+        // Test the embedded-io write makes progress and write_all completes
+        set_lsr(&mock, 0x60);
+        assert_eq!(embedded_io::Write::write(&mut uart, &[1, 2]), Ok(2));
+        assert_eq!(embedded_io::Write::write_all(&mut uart, &[3, 4]), Ok(()));
+    }
+
+    #[test]
+    fn blocking_uart_read_bounded() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+        let mut buf = [0; 4];
+
+        // Test an idle line gives up after the budget
+        assert_eq!(uart.read_bounded(&mut buf, 16), 0);
+
+        // Test available bytes fill the buffer
+        set_lsr(&mock, 0x61);
+        assert_eq!(uart.read_bounded(&mut buf, 16), 4);
+    }
+
+    #[test]
+    fn blocking_uart_wait_tx_below() {
+        let mock = MockUart::new();
+        let uart = BlockingUart::new(&mock, Config::new(), false);
+
+        // Test a full holding register is below any level of two or more without FIFOs
+        set_lsr(&mock.regs, 0x00);
+        uart.wait_tx_below(2);
+        uart.wait_tx_below(8);
+
+        // Test a level of one returns once THRE is set
+        set_lsr(&mock.regs, 0x20);
+        uart.wait_tx_below(1);
+
+        // Test with FIFOs the wait follows THRE whatever the level
+        let uart = BlockingUart::new(&mock, Config::new(), true);
+        assert!(uart.is_fifo_enabled());
+        uart.wait_tx_below(8);
+    }
+
+    #[test]
+    fn blocking_uart_tx_fifo_level() {
+        let mock = MockUart::new();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+
+        // Test an empty transmitter holds nothing
+        assert_eq!(uart.tx_fifo_level(), Some(0));
+
+        // Test a full holding register holds exactly one byte without FIFOs
+        set_lsr(&mock.regs, 0x00);
+        assert_eq!(uart.tx_fifo_level(), Some(1));
+
+        // Test the count is unknown with FIFOs enabled
+        assert_eq!(uart.enable_fifo(true), Ok(()));
+        assert_eq!(uart.tx_fifo_level(), None);
+    }
+
+    #[test]
+    fn blocking_uart_read_frame() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+        let mut buf = [0; 4];
+
+        // Test an idle line gives an empty frame
+        assert_eq!(uart.read_frame(&mut buf, 8), 0);
+
+        // Test a frame stops when the buffer is full
+        set_lsr(&mock, 0x61);
+        unsafe {
+            mock.rbr_thr_dll
+                .write(RbrThrDll::default().set_transmitter_data(0x42));
+        }
+        assert_eq!(uart.read_frame(&mut buf, 8), 4);
+        assert_eq!(buf, [0x42; 4]);
+    }
+
+    #[test]
+    fn blocking_uart_single_lcr_write() {
+        let mock = MockUart::new();
+        let config = Config::new()
+            .set_divisor(0x0010)
+            .set_parity_mode(ParityMode::High)
+            .set_stop_bits(StopBits::Bits2OrBits1_5)
+            .set_word_length(WordLength::Bits6);
+        unsafe {
+            mock.regs.lcr.write(Lcr::default().enable_break_control());
+        }
+
+        // Test the divisor takes two LCR writes and the line settings one more
+        let _uart = BlockingUart::new(&mock, config, false);
+        assert_eq!(mock.lcr_writes.get(), 3);
+        assert_eq!(BlockingUart::config(&mock), config);
+        assert!(mock.regs.lcr.read().is_break_control_enabled());
+    }
+
+    #[test]
+    fn blocking_uart_set_break() {
+        let mock = MockUart::new();
+        let mut uart = BlockingUart::new(&mock, Config::new().set_divisor(1), false);
+
+        // Test the break bit is read back without DLAB being toggled
+        uart.set_break(true);
+        let writes = mock.lcr_writes.get();
+        assert!(uart.is_break_set());
+        assert_eq!(mock.lcr_writes.get(), writes);
+        uart.set_break(false);
+        assert!(!uart.is_break_set());
+        assert_eq!(BlockingUart::config(&mock), Config::new().set_divisor(1));
+    }
+
+    #[test]
+    fn blocking_uart_with_temp_baud() {
+        let mock = MockUart::new();
+        let mut uart = BlockingUart::new(&mock, Config::new().set_divisor(12), false);
+
+        // Test the fast divisor applies only inside the closure
+        let divisor = uart.with_temp_baud(Hertz(1_843_200), Baud(115_200), |uart| {
+            BlockingUart::config(uart.registers()).divisor
+        });
+        assert_eq!(divisor, Some(1));
+        assert_eq!(BlockingUart::config(&mock).divisor, Some(12));
+    }
+
+    #[test]
+    fn blocking_uart_new_keeps_divisor() {
+        let mock = MockUart::new();
+        set_divisor(&mock, 0x1234);
+        mock.lcr_writes.set(0);
+        mock.divisor_writes.set(0);
+        let config = Config::new().set_parity_mode(ParityMode::Even);
+
+        // Test only the line settings are written
+        let _uart = BlockingUart::new(&mock, config, false);
+        assert_eq!(mock.lcr_writes.get(), 1);
+        assert_eq!(mock.divisor_writes.get(), 0);
+        assert_eq!(BlockingUart::config(&mock), config.set_divisor(0x1234));
+    }
+
+    #[test]
+    fn blocking_uart_adopt() {
+        let mock = mock_uart();
+        unsafe {
+            mock.lcr
+                .write(Lcr::default().set_word_length(WordLength::Bits7));
+            mock.ier_dlh
+                .write(IerDlh::default().enable_received_data_available_interrupt());
+        }
+
+        // Test adopting leaves every register untouched
+        let uart = BlockingUart::adopt(&mock);
+        assert_eq!(
+            mock.lcr.read(),
+            Lcr::default().set_word_length(WordLength::Bits7)
+        );
+        assert!(
+            mock.ier_dlh
+                .read()
+                .is_received_data_available_interrupt_enabled()
+        );
+        assert_eq!(mock.rbr_thr_dll.read(), RbrThrDll::default());
+        assert_eq!(uart.tx_burst, 1);
+    }
+
+    #[test]
+    fn blocking_uart_adopt_fifo() {
+        let mock = MockUart::new();
+        unsafe {
+            mock.write_iir_fcr(IirFcr::default().enable_fifo());
+        }
+
+        // Test FIFOs left on by a bootloader are taken over, not disabled by the next FCR write
+        let mut uart = BlockingUart::adopt(&mock);
+        assert_eq!(uart.tx_burst, FIFO_DEPTH);
+        uart.clear_rx_fifo();
+        assert!(mock.fcr().is_fifo_enable_set());
+        uart.set_receiver_trigger_level(ReceiverTriggerLevel::FifoHalfFull);
+        assert!(mock.fcr().is_fifo_enable_set());
+        assert_eq!(uart.fifo_status(), FifoStatus::Enabled);
+
+        // Test FIFOs reported as not functioning stay on, but are written byte by byte
+        mock.set_fifo_status(FifoStatus::EnabledNonFunctioning);
+        let mut uart = BlockingUart::adopt(&mock);
+        assert_eq!(uart.tx_burst, 1);
+        uart.clear_tx_fifo();
+        assert!(mock.fcr().is_fifo_enable_set());
+    }
+
+    #[test]
+    fn blocking_uart_from_params() {
+        let params = UartParams {
+            clock_frequency: Hertz(1_843_200),
+            reg_shift: 2,
+            reg_io_width: 4,
+            current_speed: Some(Baud(115200)),
+        };
+
+        // Test 32-bit registers 4 bytes apart: the divisor latch and FCR are written at their offsets
+        let mut regs = [0_u32; 8];
+        let base = regs.as_mut_ptr() as usize;
+        let uart = unsafe { BlockingUart::from_params(base, params, Config::new()) }.unwrap();
+        assert_eq!(uart.current_baud(1_843_200), 115200);
+        assert_eq!(regs[0], 1);
+        assert_eq!(regs[2], IirFcr::default().enable_fifo().bits());
+
+        // Test byte registers 1 byte apart
+        let params = UartParams {
+            reg_shift: 0,
+            reg_io_width: 1,
+            current_speed: Some(Baud(9600)),
+            ..params
+        };
+        let mut regs = [0_u8; 8];
+        let base = regs.as_mut_ptr() as usize;
+        let uart = unsafe { BlockingUart::from_params(base, params, Config::new()) }.unwrap();
+        assert_eq!(uart.current_baud(1_843_200), 9600);
+        assert_eq!(regs[0], 12);
+
+        // Test invalid layouts are refused
+        let shift = UartParams {
+            reg_shift: 3,
+            ..params
+        };
+        let width = UartParams {
+            reg_io_width: 2,
+            ..params
+        };
+        assert!(matches!(
+            unsafe { BlockingUart::from_params(base, shift, Config::new()) },
+            Err(ConfigError::InvalidRegShift)
+        ));
+        assert!(matches!(
+            unsafe { BlockingUart::from_params(base, width, Config::new()) },
+            Err(ConfigError::InvalidRegIoWidth)
+        ));
+    }
+
+    #[test]
+    fn blocking_uart_current_baud() {
+        let mock = MockUart::new();
+        let lcr = Lcr::default().set_word_length(WordLength::Bits7);
+        unsafe { mock.regs.lcr.write(lcr) };
+        set_divisor(&mock, 0x000C);
+
+        // Test the baud rate is computed from the divisor left by the firmware
+        let uart = BlockingUart::adopt(&mock);
+        assert_eq!(uart.current_baud(1_843_200), 9600);
+        assert_eq!(mock.regs.lcr.read(), lcr);
+    }
+
+    /// Records the total time waited.
+    struct RecordDelay(u64);
+
+    impl DelayNs for RecordDelay {
+        fn delay_ns(&mut self, ns: u32) {
+            self.0 += ns as u64;
+        }
+    }
+
+    #[test]
+    fn blocking_uart_read_paced() {
+        let mock = MockUart::new();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+        let mut delay = RecordDelay(0);
+
+        // Test the gap is waited after each byte, before checking for the next one
+        mock.set_rx_pending(3);
+        assert_eq!(uart.read_paced(&mut [0; 8], 1_000, &mut delay), 3);
+        assert_eq!(delay.0, 3_000);
+
+        // Test an empty receiver does not wait
+        assert_eq!(uart.read_paced(&mut [0; 8], 1_000, &mut delay), 0);
+        assert_eq!(delay.0, 3_000);
+    }
+
+    #[test]
+    fn blocking_uart_idle_gap() {
+        let mock = MockUart::new();
+        let uart = BlockingUart::new(&mock, Config::new().set_divisor(1), false);
+        let mut delay = RecordDelay(0);
+
+        // Test a Modbus RTU gap of 3.5 11-bit characters at 115200 baud
+        uart.idle_gap(Hertz(1_843_200), 39, &mut delay);
+        assert_eq!(delay.0, 39 * 8_680);
+    }
+
+    #[test]
+    fn blocking_uart_tx_burst() {
+        let mock = mock_uart();
+
+        // Test a part that does not report enabled FIFOs is written byte by byte
+        let mut uart = BlockingUart::new(&mock, Config::new(), true);
+        assert_eq!(uart.tx_burst, 1);
+
+        // Test a burst never writes past the end of the data
+        uart.tx_burst = FIFO_DEPTH;
+        assert_eq!(uart.write(&[0x55; 20]), 20);
+        assert_eq!(
+            blocking_write(&mock, &[0xAA; 3], FIFO_DEPTH, WriteStrategy::Thre),
+            3
+        );
+        assert_eq!(mock.rbr_thr_dll.read().receiver_data(), 0xAA);
+
+        // Test nothing is written while the transmit FIFO is full
+        set_lsr(&mock, 0x00);
+        assert_eq!(uart.write(&[0x55; 20]), 0);
+    }
+
+    #[test]
+    fn blocking_uart_write_nb_all() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+
+        // Test the accepted count is returned, and WouldBlock only when nothing fit
+        assert_eq!(uart.write_nb_all(&[1, 2, 3]), Ok(3));
+        assert_eq!(uart.write_nb_all(&[]), Ok(0));
+        set_lsr(&mock, 0x00);
+        assert_eq!(uart.write_nb_all(&[1, 2, 3]), Err(nb::Error::WouldBlock));
+    }
+
+    #[test]
+    fn blocking_uart_autobaud_measure() {
+        let mock = MockUart::new();
+        let mut uart = BlockingUart::new(&mock, Config::new().set_divisor(0x60), false);
+
+        // Test characters two bit times apart at 9600 baud, the first with a break, give divisor 12
+        set_lsr(&mock.regs, 0x71);
+        let mut now = 0;
+        let timer = || {
+            now += 208_333;
+            now
+        };
+        assert_eq!(
+            uart.autobaud_measure(timer, 1_843_200, 10_000_000),
+            Some(12)
+        );
+        assert_eq!(divisor(&mock), 0x60);
+        assert!(!uart.sticky_errors().break_interrupt);
+
+        // Test a silent line times out
+        set_lsr(&mock.regs, 0x60);
+        let mut now = 0;
+        let timer = || {
+            now += 1_000;
+            now
+        };
+        assert_eq!(uart.autobaud_measure(timer, 1_843_200, 10_000_000), None);
+        assert_eq!(divisor(&mock), 0x60);
+
+        // Test a line that keeps sending without a break still times out
+        set_lsr(&mock.regs, 0x61);
+        let mut now = 0;
+        let timer = || {
+            now += 1_000;
+            now
+        };
+        assert_eq!(uart.autobaud_measure(timer, 1_843_200, 10_000_000), None);
+
+        // Test a timer going backwards is rejected instead of underflowing
+        set_lsr(&mock.regs, 0x71);
+        let mut now = 1_000_000;
+        let timer = || {
+            now -= 1_000;
+            now
+        };
+        assert_eq!(uart.autobaud_measure(timer, 1_843_200, 10_000_000), None);
+        assert_eq!(divisor(&mock), 0x60);
+    }
+
+    #[test]
+    fn blocking_uart_ber_test() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+
+        // Test every byte received back intact gives no error
+        set_lsr(&mock, 0x61);
+        let result = uart.ber_test(100, 1, 10);
+        assert_eq!(
+            result,
+            BerResult {
+                bytes: 100,
+                errors: 0,
+                bit_errors: 0
+            }
+        );
+        assert_eq!(result.bit_error_rate(), 0.0);
+        assert!(!mock.mcr.read().is_loop_back_enabled());
+
+        // Test the same seed sends the same bytes
+        uart.ber_test(3, 7, 10);
+        let last = mock.rbr_thr_dll.read();
+        uart.ber_test(3, 7, 10);
+        assert_eq!(mock.rbr_thr_dll.read(), last);
+
+        // Test lost bytes count all their bits
+        set_lsr(&mock, 0x60);
+        let result = uart.ber_test(4, 1, 10);
+        assert_eq!(result.errors, 4);
+        assert_eq!(result.bit_error_rate(), 1.0);
+    }
+
+    #[test]
+    fn blocking_uart_write_with_checksum() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+
+        // Test the checksum is sent last
+        uart.write_with_checksum(b"123456789", ChecksumAlgo::Crc8(0x07));
+        assert_eq!(mock.rbr_thr_dll.read().receiver_data(), 0xF4);
+        uart.write_with_checksum(&[], ChecksumAlgo::Xor8);
+        assert_eq!(mock.rbr_thr_dll.read().receiver_data(), 0x00);
+    }
+
+    #[test]
+    fn blocking_uart_read_with_checksum() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+        set_lsr(&mock, 0x61);
+
+        // Test a frame of zeros carries a valid sum
+        let mut buf = [0xAA; 4];
+        assert_eq!(uart.read_with_checksum(&mut buf, ChecksumAlgo::Sum8), Ok(3));
+        assert_eq!(buf, [0; 4]);
+
+        // Test a frame of 0xFF bytes does not, as its payload sums to 0xFD
+        unsafe {
+            mock.rbr_thr_dll
+                .write(RbrThrDll::from_transmitter_data(0xFF))
+        };
+        assert_eq!(
+            uart.read_with_checksum(&mut buf, ChecksumAlgo::Sum8),
+            Err(ChecksumError {
+                len: 3,
+                expected: 0xFD,
+                actual: 0xFF,
+            })
+        );
+        assert_eq!(uart.read_with_checksum(&mut [], ChecksumAlgo::Xor8), Ok(0));
+    }
+
+    #[test]
+    fn blocking_uart_write_strategy() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+        set_lsr(&mock, 0x20);
+
+        // Test THRE alone is enough by default
+        uart.set_write_strategy(WriteStrategy::Thre);
+        assert!(uart.tx_has_space());
+        assert_eq!(uart.write(&[0x55]), 1);
+
+        // Test TEMT waits for the shift register as well
+        uart.set_write_strategy(WriteStrategy::Temt);
+        assert!(!uart.tx_has_space());
+        assert_eq!(uart.write(&[0x55]), 0);
+        set_lsr(&mock, 0x60);
+        assert!(uart.tx_has_space());
+        assert_eq!(uart.write(&[0x55]), 1);
+    }
+
+    #[test]
+    fn blocking_uart_force_unlock_write() {
+        let mock = MockUart::new();
+        let _uart = BlockingUart::new(&mock, Config::new().set_divisor(0x0C), false);
+        unsafe {
+            mock.regs
+                .lcr
+                .write(mock.regs.lcr.read().enable_divisor_latch_access());
+        }
+
+        // Test the message bypasses the divisor latch left enabled
+        unsafe { BlockingUart::force_unlock_write(&mock, b"!") };
+        assert_eq!(mock.regs.rbr_thr_dll.read().receiver_data(), b'!');
+        assert!(!mock.regs.lcr.read().is_divisor_latch_access_enabled());
+        assert_eq!(BlockingUart::config(&mock).divisor, Some(0x0C));
+    }
+
+    #[test]
+    fn blocking_uart_write_if_idle() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+
+        // Test writing on an idle transmitter
+        assert!(uart.write_if_idle(0xAA));
+        assert_eq!(mock.rbr_thr_dll.read().receiver_data(), 0xAA);
+
+        // Test the holding register being empty is not enough
+        set_lsr(&mock, 0x20);
+        assert!(!uart.write_if_idle(0x55));
+        assert_eq!(mock.rbr_thr_dll.read().receiver_data(), 0xAA);
+    }
+
+    #[test]
+    fn blocking_uart_transfer_in_place() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+
+        // Test a matching echo, the mock reads back the last byte written
+        set_lsr(&mock, 0x61);
+        let mut buf = [0x12, 0x34];
+        assert_eq!(uart.transfer_in_place(&mut buf, EchoMismatch::Stop, 10), 2);
+        assert_eq!(buf, [0x12, 0x34]);
+
+        // Test a missing echo times out
+        set_lsr(&mock, 0x60);
+        assert_eq!(uart.transfer_in_place(&mut buf, EchoMismatch::Stop, 10), 0);
+
+        // Test an echo with a framing error is not accepted, even when overwriting
+        set_lsr(&mock, 0x69);
+        assert_eq!(
+            uart.transfer_in_place(&mut buf, EchoMismatch::Overwrite, 10),
+            0
+        );
+        assert_eq!(buf, [0x12, 0x34]);
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn blocking_uart_transfer_in_place_counts() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+
+        // Test a byte sent without an echo is still counted as sent
+        let mut buf = [0x12, 0x34];
+        assert_eq!(uart.transfer_in_place(&mut buf, EchoMismatch::Stop, 10), 0);
+        assert_eq!(uart.tx_count(), 1);
+        assert_eq!(uart.rx_count(), 0);
+    }
+
+    #[test]
+    fn blocking_uart_read_line() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+        let mut buf = [0; 4];
+        set_lsr(&mock, 0x61);
+
+        // Test an empty line, the mock keeps returning the same byte
+        unsafe {
+            mock.rbr_thr_dll
+                .write(RbrThrDll::default().set_transmitter_data(b'\n'))
+        };
+        assert_eq!(uart.read_line(&mut buf), ReadLine::Complete(0));
+
+        // Test a line longer than the buffer is truncated
+        unsafe {
+            mock.rbr_thr_dll
+                .write(RbrThrDll::default().set_transmitter_data(b'a'))
+        };
+        assert_eq!(uart.read_line(&mut buf), ReadLine::Truncated(4));
+        assert_eq!(&buf, b"aaaa");
+    }
+
+    #[test]
+    fn blocking_uart_read_until() {
+        let mock = MockUart::new();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+        let mut buf = [0; 4];
+        mock.set_rx_pending(usize::MAX);
+        unsafe {
+            mock.regs
+                .rbr_thr_dll
+                .write(RbrThrDll::default().set_transmitter_data(b';'))
+        };
+
+        // Test a delimiter as the very first byte, with and without keeping it
+        assert_eq!(
+            uart.read_until(b';', &mut buf, false),
+            ReadLine::Complete(0)
+        );
+        assert_eq!(uart.read_until(b';', &mut buf, true), ReadLine::Complete(1));
+        assert_eq!(buf[0], b';');
+
+        // Test a kept delimiter needs room in the buffer
+        assert_eq!(uart.read_until(b';', &mut [], true), ReadLine::Truncated(0));
+
+        // Test the buffer fills before the delimiter
+        assert_eq!(
+            uart.read_until(b'\0', &mut buf, false),
+            ReadLine::Truncated(4)
+        );
+        assert_eq!(&buf, b";;;;");
+    }
+
+    #[test]
+    fn blocking_uart_read_line_str() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+        set_lsr(&mock, 0x61);
+        let set_rbr = |byte| unsafe {
+            mock.rbr_thr_dll
+                .write(RbrThrDll::default().set_transmitter_data(byte))
+        };
+
+        // Test an empty line is valid text
+        set_rbr(b'\n');
+        assert_eq!(uart.read_line_str(&mut [0; 4]), Ok(""));
+
+        // Test a lead byte followed by another lead byte is invalid
+        set_rbr(0xC3);
+        assert_eq!(
+            uart.read_line_str(&mut [0; 4]),
+            Err(Utf8LineError::InvalidByte { valid_up_to: 0 })
+        );
+
+        // Test a character cut by the end of the buffer
+        set_rbr(0xE2);
+        assert_eq!(
+            uart.read_line_str(&mut [0; 1]),
+            Err(Utf8LineError::TruncatedMultibyte { valid_up_to: 0 })
+        );
+
+        // Test valid text longer than the buffer
+        set_rbr(b'a');
+        assert_eq!(
+            uart.read_line_str(&mut [0; 4]),
+            Err(Utf8LineError::TooLong { valid_up_to: 4 })
+        );
+    }
+
+    #[test]
+    fn blocking_uart_set_dma_mode() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), true);
+        uart.set_receiver_trigger_level(ReceiverTriggerLevel::FifoHalfFull);
+
+        // Test the mode bit is written along with the other FIFO settings
+        uart.set_dma_mode(DmaMode::Multi);
+        let fcr = IirFcr::default()
+            .enable_fifo()
+            .set_receiver_trigger_level(ReceiverTriggerLevel::FifoHalfFull);
+        assert_eq!(mock.iir_fcr.read(), fcr.set_dma_mode(DmaMode::Mode1));
+        uart.set_dma_mode(DmaMode::Single);
+        assert_eq!(mock.iir_fcr.read(), fcr);
+    }
+
+    #[test]
+    fn blocking_uart_clear_fifos() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), true);
+        uart.set_receiver_trigger_level(ReceiverTriggerLevel::FifoHalfFull);
+        let fcr = IirFcr::default()
+            .enable_fifo()
+            .set_receiver_trigger_level(ReceiverTriggerLevel::FifoHalfFull);
+
+        // Test each clear sets only its own reset bit
+        uart.clear_rx_fifo();
+        assert_eq!(mock.iir_fcr.read(), fcr.reset_receiver_fifo());
+        uart.clear_tx_fifo();
+        assert_eq!(mock.iir_fcr.read(), fcr.reset_transmitter_fifo());
+    }
+
+    #[test]
+    fn blocking_uart_configure_auto_flow() {
+        let mock = MockUart::new();
+
+        // Test FIFOs are required
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+        assert_eq!(
+            uart.configure_auto_flow(ReceiverTriggerLevel::FifoHalfFull),
+            Err(ConfigError::FifoDisabled)
+        );
+        assert!(!mock.regs.mcr.read().is_auto_flow_control_enabled());
+
+        // Test FIFOs set in the FCR but reported as disabled by the IIR, as on a 16450, are refused
+        mock.set_fifo_status(FifoStatus::Disabled);
+        let mut uart = BlockingUart::new(&mock, Config::new(), true);
+        assert!(mock.fcr().is_fifo_enable_set());
+        assert_eq!(
+            uart.configure_auto_flow(ReceiverTriggerLevel::FifoHalfFull),
+            Err(ConfigError::FifoDisabled)
+        );
+        assert!(!mock.regs.mcr.read().is_auto_flow_control_enabled());
+
+        // Test the trigger level and auto flow control are applied together
+        mock.set_fifo_status(FifoStatus::Enabled);
+        let mut uart = BlockingUart::new(&mock, Config::new(), true);
+        assert_eq!(
+            uart.configure_auto_flow(ReceiverTriggerLevel::FifoHalfFull),
+            Ok(())
+        );
+        let extended = uart.extended_config();
+        assert_eq!(
+            extended.receiver_trigger_level,
+            ReceiverTriggerLevel::FifoHalfFull
+        );
+        assert!(extended.auto_flow_control);
+        assert!(extended.request_to_send);
+    }
+
+    #[test]
+    fn blocking_uart_stick_parity() {
+        let mock = mock_uart();
+        let uart = BlockingUart::new(&mock, Config::new().set_parity_mode(ParityMode::Low), false);
+
+        // Test the raw bits behind the collapsed parity mode
+        assert_eq!(uart.parity_mode(), ParityMode::Low);
+        assert!(uart.is_stick_parity());
+        assert_eq!(uart.base_parity(), Parity::Even);
+    }
+
+    #[test]
+    fn blocking_uart_sticky_errors() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+
+        // Test errors seen by a read persist after the LSR reads clean again
+        set_lsr(&mock, 0x63);
+        uart.read(&mut [0; 1]);
+        set_lsr(&mock, 0x60);
+        assert!(!uart.line_status().overrun_error);
+        let sticky = uart.sticky_errors();
+        assert!(sticky.overrun_error);
+        assert!(!sticky.data_ready);
+
+        // Test errors accumulate until cleared
+        set_lsr(&mock, 0x64);
+        uart.line_status();
+        let sticky = uart.sticky_errors();
+        assert!(sticky.overrun_error && sticky.parity_error);
+        uart.clear_sticky_errors();
+        assert_eq!(uart.sticky_errors(), LineStatus::default());
+    }
+
+    #[test]
+    fn blocking_uart_line_health() {
+        let mock = mock_uart();
+        let uart = BlockingUart::new(&mock, Config::new(), false);
+        assert_eq!(uart.line_health(8, 2), LineHealth::Idle);
+
+        // Test clean data and data with errors
+        set_lsr(&mock, 0x61);
+        assert_eq!(uart.line_health(8, 2), LineHealth::Active);
+        set_lsr(&mock, 0x69);
+        assert_eq!(uart.line_health(8, 2), LineHealth::Noisy);
+        assert!(uart.sticky_errors().framing_error);
+
+        // Test a line held low, and errors below the threshold
+        set_lsr(&mock, 0x79);
+        assert_eq!(uart.line_health(8, 2), LineHealth::Break);
+        assert_eq!(uart.line_health(1, 2), LineHealth::Active);
+    }
+
+    #[test]
+    fn blocking_uart_break_suppresses_framing_error() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+
+        // Test a received break, with the zero character and framing error it produces
+        set_lsr(&mock, 0x79);
+        let status = uart.line_status();
+        assert!(status.break_interrupt);
+        assert!(!status.framing_error);
+        assert_eq!(status.error(), Some(UartError::Break));
+        assert_eq!(uart.read_checked(&mut [0; 4]), Err((0, UartError::Break)));
+
+        // Test a framing error without a break is still reported
+        set_lsr(&mock, 0x69);
+        let status = uart.line_status();
+        assert!(!status.break_interrupt);
+        assert!(status.framing_error);
+        assert_eq!(status.error(), Some(UartError::Framing));
+    }
+
+    #[test]
+    fn blocking_uart_copy_to() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+        let mut out = [0_u8; 40];
+
+        // Test nothing is forwarded without received data
+        assert_eq!(uart.copy_to(&mut &mut out[..], 40), Ok(0));
+
+        // Test forwarding stops at the limit
+        set_lsr(&mock, 0x61);
+        unsafe {
+            mock.rbr_thr_dll
+                .write(RbrThrDll::default().set_transmitter_data(0x5A))
+        };
+        assert_eq!(uart.copy_to(&mut &mut out[..], 20), Ok(20));
+        assert_eq!(&out[..20], &[0x5A; 20]);
+        assert_eq!(out[20], 0);
+    }
+
+    #[test]
+    fn blocking_uart_set_receiver_trigger_level() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), true);
+
+        // Test the trigger level is written along with the FIFO enable from the shadow
+        unsafe { mock.iir_fcr.write(IirFcr::default()) };
+        uart.set_receiver_trigger_level(ReceiverTriggerLevel::FifoHalfFull);
+        assert_eq!(
+            mock.iir_fcr.read(),
+            IirFcr::default()
+                .enable_fifo()
+                .set_receiver_trigger_level(ReceiverTriggerLevel::FifoHalfFull)
+        );
+    }
+
+    #[test]
+    fn blocking_uart_enable_fifo() {
+        let mock = MockUart::new();
+
+        // Test a part that does not report enabled FIFOs is rejected and restored
+        mock.set_fifo_status(FifoStatus::Disabled);
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+        assert_eq!(uart.enable_fifo(true), Err(UartError::Unsupported));
+        assert!(!mock.fcr().is_fifo_enable_set());
+        assert_eq!(uart.tx_burst, 1);
+
+        // Test FIFOs confirmed through the IIR keep the trigger level and allow bursts
+        mock.set_fifo_status(FifoStatus::Enabled);
+        uart.set_receiver_trigger_level(ReceiverTriggerLevel::FifoHalfFull);
+        assert_eq!(uart.enable_fifo(true), Ok(()));
+        assert_eq!(uart.fifo_status(), FifoStatus::Enabled);
+        assert_eq!(
+            mock.fcr().receiver_trigger_level(),
+            ReceiverTriggerLevel::FifoHalfFull
+        );
+        assert!(uart.is_fifo_enabled());
+        assert_eq!(uart.tx_burst, FIFO_DEPTH);
+
+        // Test disabling goes back to byte by byte writes
+        assert_eq!(uart.enable_fifo(false), Ok(()));
+        assert!(!mock.fcr().is_fifo_enable_set());
+        assert_eq!(uart.tx_burst, 1);
+
+        // Test FIFOs reported as not functioning are refused unless forced
+        mock.set_fifo_status(FifoStatus::EnabledNonFunctioning);
+        assert_eq!(uart.enable_fifo(true), Err(UartError::Unsupported));
+        assert!(!mock.fcr().is_fifo_enable_set());
+        assert_eq!(uart.force_enable_fifo(), Ok(()));
+        assert_eq!(uart.fifo_status(), FifoStatus::EnabledNonFunctioning);
+        assert_eq!(uart.tx_burst, FIFO_DEPTH);
+    }
+
+    #[test]
+    fn blocking_uart_enable_64byte_fifo() {
+        let mock = MockUart::new();
+
+        // Test a part that does not confirm the mode is rejected and restored
+        let mut uart = BlockingUart::new(&mock, Config::new(), true);
+        assert_eq!(uart.enable_64byte_fifo(true), Err(UartError::Unsupported));
+        assert!(!mock.fcr().is_64byte_fifo_enabled());
+        assert_eq!(uart.tx_burst, FIFO_DEPTH);
+
+        // Test the mode is written with DLAB set and confirmed through the IIR
+        mock.set_fifo64(true);
+        assert_eq!(uart.enable_64byte_fifo(true), Ok(()));
+        assert!(mock.read_iir_fcr().is_64byte_fifo_enabled());
+        assert!(!mock.regs.lcr.read().is_divisor_latch_access_enabled());
+        assert_eq!(uart.tx_burst, FIFO_DEPTH_64);
+
+        // Test disabling keeps the other FIFO settings
+        assert_eq!(uart.enable_64byte_fifo(false), Ok(()));
+        assert_eq!(mock.fcr(), IirFcr::default().enable_fifo());
+        assert_eq!(uart.tx_burst, FIFO_DEPTH);
+    }
+
+    #[test]
+    fn blocking_uart_max_flush_time_us() {
+        let mock = mock_uart();
+        let config = Config::new().set_word_length(WordLength::Bits8);
+
+        // Test a holding register and shift register of 10-bit characters
+        let uart = BlockingUart::new(&mock, config, false);
+        assert_eq!(uart.max_flush_time_us(Baud(115200)), 173);
+        assert_eq!(uart.max_flush_time_us(Baud(0)), u32::MAX);
+
+        // Test a 16-byte FIFO with parity and two stop bits
+        let config = config
+            .set_parity_mode(ParityMode::Even)
+            .set_stop_bits(StopBits::Bits2OrBits1_5);
+        let uart = BlockingUart::new(&mock, config, true);
+        assert_eq!(uart.max_flush_time_us(Baud(9600)), 21250);
+    }
+
+    #[test]
+    fn blocking_uart_flush_timeout() {
+        let mock = mock_uart();
+        let uart = BlockingUart::new(&mock, Config::new(), false);
+        assert!(uart.flush_timeout(10));
+
+        // Test a stalled transmitter returns after the limit
+        set_lsr(&mock, 0x20);
+        assert!(!uart.flush_timeout(10));
+    }
+
+    #[test]
+    fn blocking_uart_raw_iir() {
+        let mock = mock_uart();
+        let uart = BlockingUart::adopt(&mock);
+
+        // Test the raw value matches the decoded one: FIFOs enabled, received data available
+        unsafe { (&mock.iir_fcr as *const _ as *mut u32).write_volatile(0xC4) };
+        assert_eq!(uart.raw_iir(), 0xC4);
+        assert_eq!(uart.interrupt_id(), InterruptId::ReceivedDataAvailable);
+    }
+
+    #[test]
+    fn blocking_uart_interrupt_mask() {
+        let mock = mock_uart();
+        let uart = BlockingUart::new(&mock, Config::new(), false);
+        let ier = IerDlh::default()
+            .enable_received_data_available_interrupt()
+            .enable_receiver_line_status_interrupt();
+        unsafe { mock.ier_dlh.write(ier) };
+        let expected = InterruptMask {
+            received_data_available: true,
+            receiver_line_status: true,
+            ..Default::default()
+        };
+        assert_eq!(uart.interrupt_mask(), expected);
+
+        // Test a set DLAB is cleared for the read and restored afterwards
+        let lcr = mock.lcr.read().enable_divisor_latch_access();
+        unsafe { mock.lcr.write(lcr) };
+        assert_eq!(uart.interrupt_mask(), expected);
+        assert_eq!(mock.lcr.read(), lcr);
+    }
+
+    #[test]
+    fn blocking_uart_enable_interrupts() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+        let mask = InterruptMask {
+            received_data_available: true,
+            receiver_line_status: true,
+            ..InterruptMask::default()
+        };
+
+        // Test OUT2 is left alone unless requested
+        uart.enable_interrupts(mask, false);
+        assert_eq!(uart.interrupt_mask(), mask);
+        assert!(!mock.mcr.read().out2());
+
+        // Test OUT2 is set for PC-style interrupt gating
+        uart.enable_interrupts(InterruptMask::default(), true);
+        assert_eq!(uart.interrupt_mask(), InterruptMask::default());
+        assert!(mock.mcr.read().out2());
+    }
+
+    #[test]
+    fn blocking_uart_extended_config() {
+        let mock = mock_uart();
+        let config = Config::new().set_divisor(0x0060);
+        let mut uart = BlockingUart::new(&mock, config, true);
+        uart.set_receiver_trigger_level(ReceiverTriggerLevel::FifoHalfFull);
+        unsafe {
+            mock.mcr.write(mock.mcr.read().set_request_to_send(true));
+        }
+
+        // Test shadow and live fields are combined
+        let extended = uart.extended_config();
+        assert_eq!(extended.config, config);
+        assert!(extended.fifo_enabled);
+        assert!(!extended.fifo_64byte);
+        assert_eq!(
+            extended.receiver_trigger_level,
+            ReceiverTriggerLevel::FifoHalfFull
+        );
+        assert!(!extended.auto_flow_control);
+        assert_eq!(extended.interrupt_mask, InterruptMask::default());
+        assert!(extended.request_to_send);
+        assert!(!extended.data_terminal_ready);
+    }
+
+    #[test]
+    fn blocking_uart_write_all_timeout() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+
+        // Test everything is sent on an idle transmitter
+        assert!(uart.write_all_timeout(&[0x55; 20], 10).is_empty());
+
+        // Test the unsent remainder is returned on a stalled transmitter
+        set_lsr(&mock, 0x00);
+        let buf = [0x55; 20];
+        assert_eq!(uart.write_all_timeout(&buf, 10), &buf);
+    }
+
+    #[test]
+    fn blocking_uart_send_pattern() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+
+        // Test the last byte written is the pattern
+        uart.send_pattern(0x33, 20);
+        assert_eq!(mock.rbr_thr_dll.read().receiver_data(), 0x33);
+
+        // Test the square wave alternates, ending on the right half period
+        uart.send_square_wave(19);
+        assert_eq!(mock.rbr_thr_dll.read().receiver_data(), 0x00);
+        uart.send_square_wave(2);
+        assert_eq!(mock.rbr_thr_dll.read().receiver_data(), 0xFF);
+    }
+
+    #[test]
+    fn blocking_uart_set_out1() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+        unsafe {
+            mock.mcr.write(
+                mock.mcr
+                    .read()
+                    .set_request_to_send(true)
+                    .set_out2(true)
+                    .enable_loop_back(),
+            );
+        }
+        assert!(!uart.out1());
+
+        // Test OUT1 is driven without disturbing the other bits
+        uart.set_out1(true);
+        assert!(uart.out1());
+        let mcr = mock.mcr.read();
+        assert!(mcr.out1() && mcr.request_to_send() && mcr.out2() && mcr.is_loop_back_enabled());
+
+        uart.set_out1(false);
+        assert!(!uart.out1());
+        assert!(!mock.mcr.read().out1());
+        assert!(mock.mcr.read().out2());
+    }
+
+    #[test]
+    fn blocking_uart_write_with_progress() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+        let mut progress = [0; 4];
+        let mut calls = 0;
+
+        // Test every chunk of the FIFO depth is reported
+        uart.write_with_progress(&[0x55; 40], |sent| {
+            progress[calls] = sent;
+            calls += 1;
+        });
+        assert_eq!(calls, 3);
+        assert_eq!(&progress[..3], &[16, 32, 40]);
+    }
+
+    #[test]
+    fn blocking_uart_is_present() {
+        let mock = mock_uart();
+        let uart = BlockingUart::adopt(&mock);
+        unsafe {
+            mock.scr.write(mock.scr.read().set_scratchpad(0x42));
+        }
+
+        // Test a responsive scratch register is detected and restored
+        assert!(uart.is_present());
+        assert_eq!(mock.scr.read().scratchpad(), 0x42);
+        assert_eq!(mock.lcr.read(), Lcr::default());
+    }
+
+    #[test]
+    fn blocking_uart_capabilities() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+        unsafe {
+            mock.scr.write(mock.scr.read().set_scratchpad(0x42));
+        }
+
+        // Test registers that read back every bit look like a 16750 with auto flow control
+        let caps = uart.capabilities();
+        assert_eq!(caps.kind, UartKind::Uart16750);
+        assert_eq!(caps.fifo_depth, FIFO_DEPTH_64);
+        assert!(caps.has_64byte_fifo);
+        assert!(caps.has_auto_flow);
+        assert!(!caps.has_dlf);
+
+        // Test the probed registers are restored
+        assert_eq!(mock.iir_fcr.read(), IirFcr::default().disable_fifo());
+        assert!(!mock.mcr.read().is_auto_flow_control_enabled());
+        assert_eq!(mock.scr.read().scratchpad(), 0x42);
+        assert!(!mock.lcr.read().is_divisor_latch_access_enabled());
+    }
+
+    #[test]
+    fn blocking_uart_health_check() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+
+        // Test a loopback byte that never arrives fails the check
+        assert!(!uart.health_check(10));
+        assert!(!mock.mcr.read().is_loop_back_enabled());
+
+        // Test a responsive UART passes, with the scratch register restored
+        unsafe {
+            mock.scr.write(mock.scr.read().set_scratchpad(0x42));
+        }
+        set_lsr(&mock, 0x61);
+        assert!(uart.health_check(10));
+        assert_eq!(mock.scr.read().scratchpad(), 0x42);
+        assert!(!mock.mcr.read().is_loop_back_enabled());
+    }
+
+    #[test]
+    fn blocking_uart_verify_baud() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+
+        // Test the pattern received back intact passes
+        set_lsr(&mock, 0x61);
+        assert!(uart.verify_baud(10));
+        assert_eq!(mock.rbr_thr_dll.read().receiver_data(), 0x55);
+        assert!(!mock.mcr.read().is_loop_back_enabled());
+
+        // Test a framing error fails the check
+        set_lsr(&mock, 0x69);
+        assert!(!uart.verify_baud(10));
+    }
+
+    #[test]
+    fn blocking_uart_recover() {
+        let mock = mock_uart();
+        let config = Config::new()
+            .set_divisor(0x0060)
+            .set_parity_mode(ParityMode::Odd)
+            .set_word_length(WordLength::Bits7);
+        let mut uart = BlockingUart::new(&mock, config, true);
+
+        // Test recovery returns even if the data ready bit is stuck
+        set_lsr(&mock, 0x61);
+        uart.recover();
+        assert_eq!(BlockingUart::config(&mock), config);
+        assert!(!mock.lcr.read().is_divisor_latch_access_enabled());
+    }
+
+    #[test]
+    fn blocking_uart_recover_with_dlab() {
+        let mock = MockUart::new();
+        let config = Config::new().set_divisor(0x0160);
+        let mut uart = BlockingUart::new(&mock, config, false);
+        let ier = IerDlh::default().enable_received_data_available_interrupt();
+        unsafe {
+            mock.regs.ier_dlh.write(ier);
+            mock.write_lcr(mock.read_lcr().enable_divisor_latch_access());
+        }
+
+        // Test a DLAB left set is cleared, and the IER, not the divisor latch, is restored
+        uart.recover();
+        assert!(!mock.regs.lcr.read().is_divisor_latch_access_enabled());
+        assert_eq!(mock.regs.ier_dlh.read(), ier);
+        assert_eq!(BlockingUart::config(&mock), config);
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn blocking_uart_counts() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+
+        // Test both directions are counted
+        assert_eq!(uart.write(&[0x55; 3]), 3);
+        set_lsr(&mock, 0x61);
+        let mut buf = [0; 2];
+        assert_eq!(uart.read(&mut buf), 2);
+        assert_eq!(uart.tx_count(), 3);
+        assert_eq!(uart.rx_count(), 2);
+
+        // Test the counters can be reset
+        uart.reset_counts();
+        assert_eq!(uart.tx_count(), 0);
+        assert_eq!(uart.rx_count(), 0);
+    }
+
+    #[test]
+    fn blocking_uart_poll_into() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+        unsafe {
+            mock.rbr_thr_dll
+                .write(RbrThrDll::default().set_transmitter_data(0x41));
+        }
+
+        // Test bytes are passed until the receiver is empty
+        set_lsr(&mock, 0x61);
+        let mut received = 0;
+        let len = uart.poll_into(|byte| {
+            assert_eq!(byte, 0x41);
+            received += 1;
+            if received == 3 {
+                set_lsr(&mock, 0x60);
+            }
+        });
+        assert_eq!(len, 3);
+
+        // Test an errored byte is replaced by its error
+        set_lsr(&mock, 0x65);
+        let mut events = [Ok(0); 2];
+        let mut count = 0;
+        let len = uart.poll_into_checked(|event| {
+            events[count] = event;
+            count += 1;
+            set_lsr(&mock, if count == 1 { 0x61 } else { 0x60 });
+        });
+        assert_eq!(len, 1);
+        assert_eq!(events, [Err(UartError::Parity), Ok(0x41)]);
+    }
+
+    #[test]
+    fn blocking_uart_has_fifo_error() {
+        let mock = mock_uart();
+        let uart = BlockingUart::new(&mock, Config::new(), true);
+        assert!(!uart.has_fifo_error());
+
+        // Test the FIFO error bit is reported
+        set_lsr(&mock, 0xE1);
+        assert!(uart.has_fifo_error());
+        assert!(uart.line_status().fifo_error);
+    }
+
+    #[test]
+    fn blocking_uart_read_checked() {
+        let mock = mock_uart();
+        let mut uart = BlockingUart::new(&mock, Config::new(), false);
+        let mut buf = [0; 4];
+
+        // Test reading clean data fills the buffer
+        set_lsr(&mock, 0x61);
+        assert_eq!(uart.read_checked(&mut buf), Ok(4));
+
+        // Test no data available
+        set_lsr(&mock, 0x60);
+        assert_eq!(uart.read_checked(&mut buf), Ok(0));
+
+        // Test errors stop the read at the offending byte
+        set_lsr(&mock, 0x65);
+        assert_eq!(uart.read_checked(&mut buf), Err((0, UartError::Parity)));
+        set_lsr(&mock, 0x69);
+        assert_eq!(uart.read_checked(&mut buf), Err((0, UartError::Framing)));
+        set_lsr(&mock, 0x79);
+        assert_eq!(uart.read_checked(&mut buf), Err((0, UartError::Break)));
+
+        // Test overrun keeps the intact byte
+        set_lsr(&mock, 0x63);
+        assert_eq!(uart.read_checked(&mut buf), Err((1, UartError::Overrun)));
+
+        // Test the same errors are seen when checking after the read
+        uart.set_error_check_order(ErrorCheckOrder::RbrThenLsr);
+        set_lsr(&mock, 0x61);
+        assert_eq!(uart.read_checked(&mut buf), Ok(4));
+        set_lsr(&mock, 0x65);
+        assert_eq!(uart.read_checked(&mut buf), Err((0, UartError::Parity)));
+
+        // Test ignored parity errors keep the byte, but not other errors
+        uart.set_ignore_rx_parity(true);
+        assert_eq!(uart.read_checked(&mut buf), Ok(4));
+        assert!(uart.sticky_errors().parity_error);
+        set_lsr(&mock, 0x67);
+        assert_eq!(uart.read_checked(&mut buf), Err((1, UartError::Overrun)));
     }
 }
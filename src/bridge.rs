@@ -0,0 +1,163 @@
+use crate::BlockingUart;
+use crate::register::UartRegisters;
+
+/// A transparent pass-through between two UARTs, for serial repeaters and gateways.
+///
+/// Each [`poll`](Self::poll) moves the bytes that are ready from the receiver of each UART
+/// to the transmitter of the other, without waiting. Only as many bytes are taken from
+/// a receiver as the other transmitter can accept at once, so a busy direction never holds up
+/// the other one, and bytes stay in the receiver FIFO until there is room for them.
+/// If a transmitter still takes fewer bytes than expected, the rest is kept and sent first
+/// on the next poll, so no byte is lost. The two UARTs may use different register backends.
+pub struct Bridge<A, B> {
+    a: BlockingUart<A>,
+    b: BlockingUart<B>,
+    drop_errors: bool,
+    a_to_b: Pending,
+    b_to_a: Pending,
+}
+
+/// Bytes taken from one receiver that the other transmitter has not accepted yet.
+struct Pending {
+    buf: [u8; 64],
+    start: usize,
+    end: usize,
+}
+
+impl Pending {
+    const EMPTY: Pending = Pending {
+        buf: [0; 64],
+        start: 0,
+        end: 0,
+    };
+}
+
+impl<A: UartRegisters, B: UartRegisters> Bridge<A, B> {
+    /// Creates a new Bridge between two UARTs.
+    ///
+    /// If `drop_errors` is set, bytes received with a parity, framing or break error are
+    /// dropped instead of forwarded, as by [`BlockingUart::read_checked`].
+    pub fn new(a: BlockingUart<A>, b: BlockingUart<B>, drop_errors: bool) -> Self {
+        Bridge {
+            a,
+            b,
+            drop_errors,
+            a_to_b: Pending::EMPTY,
+            b_to_a: Pending::EMPTY,
+        }
+    }
+
+    /// Forwards the bytes that are ready in both directions, without waiting.
+    ///
+    /// Returns the number of bytes forwarded from A to B, and from B to A.
+    pub fn poll(&mut self) -> (usize, usize) {
+        let a_to_b = forward(&mut self.a, &mut self.b, &mut self.a_to_b, self.drop_errors);
+        let b_to_a = forward(&mut self.b, &mut self.a, &mut self.b_to_a, self.drop_errors);
+        (a_to_b, b_to_a)
+    }
+
+    /// Consumes the Bridge, returning the two UARTs.
+    pub fn into_inner(self) -> (BlockingUart<A>, BlockingUart<B>) {
+        (self.a, self.b)
+    }
+}
+
+/// Moves up to one transmitter burst of received bytes from `src` to `dst`.
+///
+/// Room is checked with the write strategy of `dst`, the same check its writes use, before
+/// anything is read. Bytes left over from an earlier short write are sent before new ones
+/// are read, and any bytes `dst` does not accept now are kept in `pending`.
+fn forward(
+    src: &mut BlockingUart<impl UartRegisters>,
+    dst: &mut BlockingUart<impl UartRegisters>,
+    pending: &mut Pending,
+    drop_errors: bool,
+) -> usize {
+    if !dst.tx_ready() {
+        return 0;
+    }
+    if pending.start == pending.end {
+        let chunk = &mut pending.buf[..dst.tx_burst()];
+        let len = match drop_errors {
+            true => {
+                // One received byte per iteration, whether it is kept or dropped.
+                let mut len = 0_usize;
+                for _ in 0..chunk.len() {
+                    match src.read_checked(&mut chunk[len..len + 1]) {
+                        Ok(0) => break,
+                        Ok(good) | Err((good, _)) => len += good,
+                    }
+                }
+                len
+            }
+            false => src.read(chunk),
+        };
+        pending.start = 0;
+        pending.end = len;
+    }
+    let sent = dst.write(&pending.buf[pending.start..pending.end]);
+    pending.start += sent;
+    sent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockUart, mock_uart, set_lsr};
+    use crate::register::RbrThrDll;
+    use crate::{Config, WriteStrategy};
+
+    #[test]
+    fn bridge_poll() {
+        let mock_a = mock_uart();
+        let mock_b = mock_uart();
+        let a = BlockingUart::new(&mock_a, Config::new(), false);
+        let b = BlockingUart::new(&mock_b, Config::new(), false);
+        let mut bridge = Bridge::new(a, b, true);
+
+        // Test an idle line forwards nothing
+        assert_eq!(bridge.poll(), (0, 0));
+
+        // Test a byte received on A is sent on B, one transmitter burst at a time
+        set_lsr(&mock_a, 0x61);
+        unsafe {
+            mock_a
+                .rbr_thr_dll
+                .write(RbrThrDll::default().set_transmitter_data(0x42));
+        }
+        assert_eq!(bridge.poll(), (1, 0));
+        assert_eq!(mock_b.rbr_thr_dll.read().receiver_data(), 0x42);
+
+        // Test a byte with a framing error is dropped
+        set_lsr(&mock_a, 0x69);
+        assert_eq!(bridge.poll().0, 0);
+    }
+
+    #[test]
+    fn bridge_poll_temt_strategy() {
+        let mock_a = MockUart::new();
+        let mock_b = mock_uart();
+        let a = BlockingUart::new(&mock_a, Config::new(), false);
+        let mut b = BlockingUart::new(&mock_b, Config::new(), false);
+        b.set_write_strategy(WriteStrategy::Temt);
+        let mut bridge = Bridge::new(a, b, false);
+        unsafe {
+            mock_a
+                .regs
+                .rbr_thr_dll
+                .write(RbrThrDll::default().set_transmitter_data(0x42));
+        }
+        mock_a.set_rx_pending(1);
+
+        // Test nothing is taken from A while B still shifts out a byte (THRE set, TEMT clear)
+        set_lsr(&mock_b, 0x20);
+        assert_eq!(bridge.poll(), (0, 0));
+        assert!(mock_a.read_lsr().is_data_ready());
+
+        // Test the byte is forwarded once the transmitter of B is empty
+        set_lsr(&mock_b, 0x60);
+        assert_eq!(bridge.poll(), (1, 0));
+        assert_eq!(mock_b.rbr_thr_dll.read().receiver_data(), 0x42);
+        assert!(!mock_a.read_lsr().is_data_ready());
+    }
+}
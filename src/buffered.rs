@@ -0,0 +1,293 @@
+#![allow(unused)]
+
+use crate::BlockingUart;
+use crate::ConfigError;
+use crate::register::{RegisterBlock, UartRegisters};
+use core::ops::Deref;
+
+/// Represents the action taken when the receive buffer of a [`BufferedUart`] is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Discard newly received bytes.
+    DropNewest,
+    /// Overwrite the oldest buffered bytes.
+    DropOldest,
+    /// Leave new bytes in the hardware FIFO and deassert RTS until space is available.
+    ///
+    /// RTS is deasserted at the high watermark and asserted again at the low watermark,
+    /// see [`BufferedUart::with_watermarks`].
+    Backpressure,
+}
+
+/// A fixed-capacity ring buffer of bytes.
+struct RingBuffer<const N: usize> {
+    buf: [u8; N],
+    head: usize,
+    len: usize,
+}
+
+impl<const N: usize> RingBuffer<N> {
+    const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    const fn len(&self) -> usize {
+        self.len
+    }
+
+    const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Appends a byte, returning false if the buffer is full.
+    fn push(&mut self, byte: u8) -> bool {
+        if self.is_full() {
+            return false;
+        }
+        self.buf[(self.head + self.len) % N] = byte;
+        self.len += 1;
+        true
+    }
+
+    /// Removes and returns the oldest byte.
+    fn pop(&mut self) -> Option<u8> {
+        if self.len == 0 {
+            return None;
+        }
+        let byte = self.buf[self.head];
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        Some(byte)
+    }
+}
+
+/// A UART receiver backed by a software ring buffer of `N` bytes.
+///
+/// Bytes are moved from the hardware into the ring buffer by [`poll`](Self::poll),
+/// and consumed by [`read`](Self::read).
+/// When the ring buffer is full, the configured [`OverflowPolicy`] decides what happens to new bytes.
+pub struct BufferedUart<UART, const N: usize> {
+    uart: BlockingUart<UART>,
+    ring: RingBuffer<N>,
+    policy: OverflowPolicy,
+    dropped: usize,
+    high_watermark: usize,
+    low_watermark: usize,
+    /// RTS was deasserted at the high watermark.
+    throttled: bool,
+}
+
+impl<UART: UartRegisters, const N: usize> BufferedUart<UART, N> {
+    /// Creates a new BufferedUart with the specified overflow policy.
+    ///
+    /// With [`OverflowPolicy::Backpressure`], RTS is deasserted when the ring buffer is 90% full,
+    /// leaving room for the bytes the peer sends before it reacts, and asserted again when it is half empty.
+    /// The low watermark is kept below the high one, so small buffers get valid watermarks too.
+    pub fn new(uart: BlockingUart<UART>, policy: OverflowPolicy) -> Self {
+        const { assert!(N >= 2, "ring size must be at least 2") };
+        let high = (N * 9 / 10).max(1);
+        let low = (N / 2).min(high - 1);
+        Self::build(uart, policy, high, low)
+    }
+
+    /// Creates a new BufferedUart with explicit flow control watermarks.
+    ///
+    /// With [`OverflowPolicy::Backpressure`], RTS is deasserted once `high` bytes are buffered,
+    /// and asserted again once no more than `low` bytes are left. The gap between the two
+    /// keeps RTS from toggling with every byte when the buffer is nearly full.
+    /// The watermarks are ignored by the other policies.
+    /// Returns [`ConfigError::InvalidWatermarks`] unless `low < high <= N`.
+    pub fn with_watermarks(
+        uart: BlockingUart<UART>,
+        policy: OverflowPolicy,
+        high: usize,
+        low: usize,
+    ) -> Result<Self, ConfigError> {
+        if low >= high || high > N {
+            return Err(ConfigError::InvalidWatermarks);
+        }
+        Ok(Self::build(uart, policy, high, low))
+    }
+
+    fn build(uart: BlockingUart<UART>, policy: OverflowPolicy, high: usize, low: usize) -> Self {
+        BufferedUart {
+            uart,
+            ring: RingBuffer::new(),
+            policy,
+            dropped: 0,
+            high_watermark: high,
+            low_watermark: low,
+            throttled: false,
+        }
+    }
+
+    /// Moves all bytes available in the hardware into the ring buffer.
+    ///
+    /// Returns the number of bytes stored.
+    pub fn poll(&mut self) -> usize {
+        let mut count = 0_usize;
+        let mut byte = [0];
+        loop {
+            if self.ring.is_full() && self.policy == OverflowPolicy::Backpressure {
+                break;
+            }
+            if self.uart.read(&mut byte) == 0 {
+                break;
+            }
+            if self.ring.is_full() {
+                self.dropped += 1;
+                match self.policy {
+                    OverflowPolicy::DropNewest => continue,
+                    _ => {
+                        self.ring.pop();
+                    }
+                }
+            }
+            self.ring.push(byte[0]);
+            count += 1;
+            if self.policy == OverflowPolicy::Backpressure
+                && !self.throttled
+                && self.ring.len() >= self.high_watermark
+            {
+                self.set_request_to_send(false);
+                self.throttled = true;
+            }
+        }
+        count
+    }
+
+    /// Reads buffered data into the provided buffer.
+    ///
+    /// Returns the number of bytes actually read.
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        self.poll();
+        let mut count = 0_usize;
+        for ch in buf {
+            match self.ring.pop() {
+                Some(byte) => *ch = byte,
+                None => break,
+            }
+            count += 1;
+        }
+        if self.throttled && self.ring.len() <= self.low_watermark {
+            self.set_request_to_send(true);
+            self.throttled = false;
+        }
+        count
+    }
+
+    /// Returns the number of bytes waiting in the ring buffer.
+    pub fn available(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Returns the number of received bytes dropped because the ring buffer was full.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Gets a reference to the register accessor of the wrapped BlockingUart.
+    pub(crate) fn registers(&self) -> &UART {
+        self.uart.registers()
+    }
+
+    /// Returns a mutable reference to the wrapped BlockingUart.
+    pub fn inner_mut(&mut self) -> &mut BlockingUart<UART> {
+        &mut self.uart
+    }
+
+    /// Consumes the BufferedUart, returning the wrapped BlockingUart.
+    ///
+    /// Any bytes still in the ring buffer are discarded.
+    pub fn into_inner(self) -> BlockingUart<UART> {
+        self.uart
+    }
+
+    fn set_request_to_send(&self, val: bool) {
+        let uart = self.uart.registers();
+        let mcr = uart.read_mcr().set_request_to_send(val);
+        unsafe {
+            uart.write_mcr(mcr);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use crate::mock::{mock_uart, set_lsr};
+    #[test]
+    fn struct_ring_buffer_functions() {
+        let mut ring = RingBuffer::<3>::new();
+        assert_eq!(ring.pop(), None);
+
+        // Test filling the buffer
+        assert!(ring.push(1));
+        assert!(ring.push(2));
+        assert!(ring.push(3));
+        assert!(ring.is_full());
+        assert!(!ring.push(4));
+        assert_eq!(ring.len(), 3);
+
+        // Test wrapping around the end of the storage
+        assert_eq!(ring.pop(), Some(1));
+        assert!(ring.push(4));
+        assert_eq!(ring.pop(), Some(2));
+        assert_eq!(ring.pop(), Some(3));
+        assert_eq!(ring.pop(), Some(4));
+        assert_eq!(ring.pop(), None);
+        assert_eq!(ring.len(), 0);
+    }
+
+    #[test]
+    fn buffered_uart_default_watermarks() {
+        let mock = mock_uart();
+
+        // Test the smallest buffer gets valid default watermarks
+        let uart = BlockingUart::new(&mock, Config::new(), false);
+        let buffered = BufferedUart::<_, 2>::new(uart, OverflowPolicy::Backpressure);
+        assert_eq!(buffered.high_watermark, 1);
+        assert_eq!(buffered.low_watermark, 0);
+
+        let uart = BlockingUart::new(&mock, Config::new(), false);
+        let buffered = BufferedUart::<_, 10>::new(uart, OverflowPolicy::Backpressure);
+        assert_eq!(buffered.high_watermark, 9);
+        assert_eq!(buffered.low_watermark, 5);
+    }
+
+    #[test]
+    fn buffered_uart_watermarks() {
+        let mock = mock_uart();
+        let uart = BlockingUart::new(&mock, Config::new(), false);
+        assert!(matches!(
+            BufferedUart::<_, 10>::with_watermarks(uart, OverflowPolicy::Backpressure, 4, 4),
+            Err(ConfigError::InvalidWatermarks)
+        ));
+
+        let uart = BlockingUart::new(&mock, Config::new(), false);
+        let mut buffered =
+            BufferedUart::<_, 10>::with_watermarks(uart, OverflowPolicy::Backpressure, 8, 4)
+                .unwrap();
+        unsafe {
+            mock.mcr.write(mock.mcr.read().set_request_to_send(true));
+        }
+
+        // Test RTS is deasserted at the high watermark, and the buffer still fills up
+        set_lsr(&mock, 0x61);
+        assert_eq!(buffered.poll(), 10);
+        assert!(!mock.mcr.read().request_to_send());
+
+        // Test RTS stays deasserted until the low watermark is reached
+        set_lsr(&mock, 0x60);
+        let mut buf = [0; 5];
+        assert_eq!(buffered.read(&mut buf), 5);
+        assert!(!mock.mcr.read().request_to_send());
+        assert_eq!(buffered.read(&mut buf[..1]), 1);
+        assert!(mock.mcr.read().request_to_send());
+    }
+}
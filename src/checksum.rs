@@ -0,0 +1,75 @@
+/// Represents the one-byte checksums appended to frames by simple protocols.
+///
+/// The same computation backs [`BlockingUart::write_with_checksum`](crate::BlockingUart::write_with_checksum),
+/// [`BlockingUart::read_with_checksum`](crate::BlockingUart::read_with_checksum) and [`verify`](Self::verify),
+/// so both ends of a link agree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgo {
+    /// The sum of the bytes, modulo 256.
+    Sum8,
+    /// The XOR of the bytes.
+    Xor8,
+    /// A CRC-8 with the given polynomial, without its implicit top bit, e.g. `0x07` for CRC-8/SMBUS.
+    ///
+    /// The register starts at 0, bits are processed most significant first, and the result is not inverted.
+    Crc8(u8),
+}
+
+impl ChecksumAlgo {
+    /// Computes the checksum of `data`.
+    pub const fn compute(self, data: &[u8]) -> u8 {
+        let mut acc = 0_u8;
+        let mut i = 0;
+        while i < data.len() {
+            let byte = data[i];
+            acc = match self {
+                ChecksumAlgo::Sum8 => acc.wrapping_add(byte),
+                ChecksumAlgo::Xor8 => acc ^ byte,
+                ChecksumAlgo::Crc8(poly) => {
+                    let mut crc = acc ^ byte;
+                    let mut bit = 0;
+                    while bit < 8 {
+                        crc = match crc & 0x80 {
+                            0 => crc << 1,
+                            _ => (crc << 1) ^ poly,
+                        };
+                        bit += 1;
+                    }
+                    crc
+                }
+            };
+            i += 1;
+        }
+        acc
+    }
+
+    /// Checks a received frame whose last byte is the checksum of the bytes before it.
+    ///
+    /// Returns false for an empty frame, which has no checksum byte.
+    pub const fn verify(self, frame: &[u8]) -> bool {
+        match frame.split_last() {
+            Some((&checksum, payload)) => self.compute(payload) == checksum,
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum_algorithms() {
+        // Test the standard check value of each algorithm
+        let data = b"123456789";
+        assert_eq!(ChecksumAlgo::Sum8.compute(data), 0xDD);
+        assert_eq!(ChecksumAlgo::Xor8.compute(data), 0x31);
+        assert_eq!(ChecksumAlgo::Crc8(0x07).compute(data), 0xF4);
+        assert_eq!(ChecksumAlgo::Crc8(0x07).compute(&[]), 0x00);
+
+        // Test a frame verifies only with its own checksum appended
+        assert!(ChecksumAlgo::Crc8(0x07).verify(b"123456789\xF4"));
+        assert!(!ChecksumAlgo::Crc8(0x07).verify(b"123456789\xF5"));
+        assert!(!ChecksumAlgo::Sum8.verify(&[]));
+    }
+}
@@ -0,0 +1,209 @@
+#![allow(unused)]
+#![allow(clippy::identity_op)]
+
+use crate::UartError;
+use crate::register::RegisterBlock;
+use crate::wait::{DefaultWait, WaitStrategy};
+use core::ops::Deref;
+use volatile_register::RO;
+
+/// Synopsys DesignWare APB UART Register Block.
+///
+/// The DesignWare UART is a 16550-compatible core with additional vendor registers.
+/// The first eight registers are identical to the standard 16550 layout,
+/// and this structure dereferences to the standard [`RegisterBlock`] for them.
+#[repr(C)]
+pub struct DwRegisterBlock {
+    /// Standard 16550 registers.
+    pub uart: RegisterBlock,
+    _reserved0: [u32; 24],
+    /// Transmit FIFO Level Register.
+    pub tfl: RO<Tfl>,
+    /// Receive FIFO Level Register.
+    pub rfl: RO<Rfl>,
+    _reserved1: [u32; 27],
+    /// Component Parameter Register.
+    pub cpr: RO<Cpr>,
+}
+
+impl Deref for DwRegisterBlock {
+    type Target = RegisterBlock;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.uart
+    }
+}
+
+impl DwRegisterBlock {
+    /// Gets the number of bytes currently in the transmit FIFO.
+    ///
+    /// The level registers are only present when [`Cpr::has_fifo_status`] is set.
+    #[inline]
+    pub fn tx_fifo_level(&self) -> usize {
+        self.tfl.read().level()
+    }
+
+    /// Waits until fewer than `level` bytes are in the transmit FIFO.
+    ///
+    /// This is exact, as it polls the TFL register, which requires [`Cpr::has_fifo_status`].
+    /// A `level` of 0 is treated as 1, i.e. waiting for an empty FIFO.
+    pub fn wait_tx_below(&self, level: usize) {
+        while self.tx_fifo_level() >= level.max(1) {
+            DefaultWait::wait();
+        }
+    }
+
+    /// Gets the number of bytes currently in the receive FIFO.
+    #[inline]
+    pub fn rx_fifo_level(&self) -> usize {
+        self.rfl.read().level()
+    }
+
+    /// Enables or disables IrDA SIR mode.
+    ///
+    /// In SIR mode the serial data is pulse-shaped for an infrared transceiver.
+    /// This is only available when the core was configured with IrDA support,
+    /// as reported by [`Cpr::has_sir_mode`]; otherwise returns [`UartError::Unsupported`]
+    /// without touching any register.
+    pub fn enable_sir_mode(&self, enable: bool) -> Result<(), UartError> {
+        if !self.cpr.read().has_sir_mode() {
+            return Err(UartError::Unsupported);
+        }
+        let mcr = match enable {
+            true => self.uart.mcr.read().enable_sir_mode(),
+            false => self.uart.mcr.read().disable_sir_mode(),
+        };
+        unsafe {
+            self.uart.mcr.write(mcr);
+        }
+        Ok(())
+    }
+}
+
+/// Transmit FIFO Level Register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct Tfl(u32);
+
+impl Tfl {
+    /// Transmit FIFO Level.
+    const TFL: u32 = 0xFF << 0;
+
+    /// Gets the transmit FIFO level.
+    /// Returns the number of data entries in the transmit FIFO.
+    #[inline]
+    pub const fn level(self) -> usize {
+        (self.0 & Self::TFL) as usize
+    }
+}
+
+/// Receive FIFO Level Register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct Rfl(u32);
+
+impl Rfl {
+    /// Receive FIFO Level.
+    const RFL: u32 = 0xFF << 0;
+
+    /// Gets the receive FIFO level.
+    /// Returns the number of data entries in the receive FIFO.
+    #[inline]
+    pub const fn level(self) -> usize {
+        (self.0 & Self::RFL) as usize
+    }
+}
+
+/// Component Parameter Register.
+///
+/// Describes the options the core was configured with.
+/// It reads as zero on cores built without the encoded parameters option,
+/// in which case no optional feature can be detected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct Cpr(u32);
+
+impl Cpr {
+    /// Auto Flow Control Mode.
+    const AFCE_MODE: u32 = 0x01 << 4;
+    /// Programmable THRE Interrupt Mode.
+    const THRE_MODE: u32 = 0x01 << 5;
+    /// IrDA SIR Mode.
+    const SIR_MODE: u32 = 0x01 << 6;
+    /// FIFO Status Registers.
+    const FIFO_STAT: u32 = 0x01 << 10;
+    /// FIFO Depth.
+    const FIFO_MODE: u32 = 0xFF << 16;
+
+    /// Checks if auto flow control is available.
+    #[inline]
+    pub const fn has_auto_flow_control(self) -> bool {
+        (self.0 & Self::AFCE_MODE) != 0
+    }
+
+    /// Checks if the programmable THRE interrupt mode is available.
+    #[inline]
+    pub const fn has_programmable_thre(self) -> bool {
+        (self.0 & Self::THRE_MODE) != 0
+    }
+
+    /// Checks if IrDA SIR mode is available.
+    #[inline]
+    pub const fn has_sir_mode(self) -> bool {
+        (self.0 & Self::SIR_MODE) != 0
+    }
+
+    /// Checks if the FIFO level registers (TFL and RFL) are available.
+    #[inline]
+    pub const fn has_fifo_status(self) -> bool {
+        (self.0 & Self::FIFO_STAT) != 0
+    }
+
+    /// Gets the FIFO depth in bytes.
+    /// Returns 0 if the core was configured without FIFOs.
+    #[inline]
+    pub const fn fifo_depth(self) -> usize {
+        (((self.0 & Self::FIFO_MODE) >> 16) as usize) * 16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::mem::offset_of;
+    #[test]
+    fn struct_dw_register_block_offset() {
+        assert_eq!(offset_of!(DwRegisterBlock, uart), 0x00);
+        assert_eq!(offset_of!(DwRegisterBlock, tfl), 0x80);
+        assert_eq!(offset_of!(DwRegisterBlock, rfl), 0x84);
+        assert_eq!(offset_of!(DwRegisterBlock, cpr), 0xF4);
+    }
+    #[test]
+    fn struct_tfl_rfl_functions() {
+        assert_eq!(Tfl(0x00).level(), 0);
+        assert_eq!(Tfl(0x10).level(), 16);
+        assert_eq!(Tfl(0xFFFF_FF40).level(), 64);
+
+        assert_eq!(Rfl(0x00).level(), 0);
+        assert_eq!(Rfl(0x10).level(), 16);
+        assert_eq!(Rfl(0xFFFF_FF40).level(), 64);
+    }
+    #[test]
+    fn struct_cpr_functions() {
+        let val = Cpr(0x0);
+        assert!(!val.has_auto_flow_control());
+        assert!(!val.has_programmable_thre());
+        assert!(!val.has_sir_mode());
+        assert!(!val.has_fifo_status());
+        assert_eq!(val.fifo_depth(), 0);
+
+        // Test a core with all optional features and 64 byte FIFOs
+        let val = Cpr(0x0004_0470);
+        assert!(val.has_auto_flow_control());
+        assert!(val.has_programmable_thre());
+        assert!(val.has_sir_mode());
+        assert!(val.has_fifo_status());
+        assert_eq!(val.fifo_depth(), 64);
+    }
+}
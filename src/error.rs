@@ -0,0 +1,151 @@
+use crate::register::Lsr;
+
+/// Represents the errors reported by the UART receiver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UartError {
+    /// A received character was lost because the receiver buffer was full.
+    Overrun,
+    /// A received character had a parity mismatch.
+    Parity,
+    /// A received character did not have a valid stop bit.
+    Framing,
+    /// A break condition was detected on the line.
+    Break,
+    /// The requested feature is not supported by this part.
+    Unsupported,
+}
+
+impl UartError {
+    /// Gets the receiver error reported by a Line Status Register value, if any.
+    ///
+    /// When several error bits are set, the most significant one is returned,
+    /// in the order break, framing, parity, overrun.
+    /// In particular the framing error that accompanies every received break is reported as [`Break`](Self::Break) only.
+    #[inline]
+    pub const fn from_line_status(lsr: Lsr) -> Option<Self> {
+        if lsr.is_broken() {
+            Some(UartError::Break)
+        } else if lsr.is_framing_error() {
+            Some(UartError::Framing)
+        } else if lsr.is_parity_error() {
+            Some(UartError::Parity)
+        } else if lsr.is_overrun_error() {
+            Some(UartError::Overrun)
+        } else {
+            None
+        }
+    }
+}
+
+impl From<core::convert::Infallible> for UartError {
+    fn from(never: core::convert::Infallible) -> Self {
+        match never {}
+    }
+}
+
+/// Maps each error to the closest [`embedded_io::ErrorKind`], for generic code:
+///
+/// - [`Overrun`](UartError::Overrun) to [`Interrupted`](embedded_io::ErrorKind::Interrupted):
+///   bytes were dropped, but the receiver keeps working and the read can simply be retried.
+/// - [`Parity`](UartError::Parity) and [`Framing`](UartError::Framing) to
+///   [`InvalidData`](embedded_io::ErrorKind::InvalidData): the received character is corrupt.
+/// - [`Break`](UartError::Break) to [`Other`](embedded_io::ErrorKind::Other), as there is no close match;
+///   some protocols use a break as a signal rather than a fault.
+/// - [`Unsupported`](UartError::Unsupported) to [`Unsupported`](embedded_io::ErrorKind::Unsupported).
+///
+/// The kind loses detail, so code that knows it talks to this driver should match on `UartError` itself.
+impl embedded_io::Error for UartError {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        match self {
+            UartError::Overrun => embedded_io::ErrorKind::Interrupted,
+            UartError::Parity | UartError::Framing => embedded_io::ErrorKind::InvalidData,
+            UartError::Break => embedded_io::ErrorKind::Other,
+            UartError::Unsupported => embedded_io::ErrorKind::Unsupported,
+        }
+    }
+}
+
+/// Represents invalid or questionable configuration values.
+///
+/// Reported by [`Config::validate`](crate::Config::validate) and by constructors and methods that check their settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Two stop bits were requested with 5-bit words, which the hardware sends as 1.5 stop bits.
+    ///
+    /// This is a valid hardware setting, so callers that intend 1.5 stop bits may ignore it.
+    OneAndHalfStopBits,
+    /// The divisor is 0, which does not produce a baud rate.
+    ZeroDivisor,
+    /// The low flow control watermark is not below the high one, or the high one exceeds the buffer size.
+    InvalidWatermarks,
+    /// The requested setting or operation needs FIFOs, but the IIR does not report them as enabled.
+    ///
+    /// Burst transfers and auto flow control rely on the FIFOs; without them a burst would overflow
+    /// the one-byte holding register, and flow control would have no threshold to act on.
+    /// Check that FIFOs were requested from [`BlockingUart::new`](crate::BlockingUart::new)
+    /// or enabled with [`BlockingUart::enable_fifo`](crate::BlockingUart::enable_fifo),
+    /// and that the part has FIFOs at all; a 16450 ignores the FCR write.
+    FifoDisabled,
+    /// The FIFO depth asked for is larger than the FIFO the part confirmed.
+    ///
+    /// A [`FifoUart`](crate::FifoUart) burst of 64 bytes on a 16550A, whose FIFOs hold 16,
+    /// would silently drop the last 48 bytes.
+    FifoTooShallow,
+    /// The register shift is outside 0 to 2, see [`UartParams`](crate::UartParams).
+    InvalidRegShift,
+    /// The register access width is neither 1 nor 4 bytes, see [`UartParams`](crate::UartParams).
+    InvalidRegIoWidth,
+}
+
+/// Represents the failures of [`BlockingUart::read_line_str`](crate::BlockingUart::read_line_str).
+///
+/// Each variant holds the length of the valid UTF-8 prefix stored in the buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Utf8LineError {
+    /// The line holds a byte that cannot start or continue a UTF-8 sequence.
+    InvalidByte {
+        /// The length of the valid prefix.
+        valid_up_to: usize,
+    },
+    /// The line ends inside a multibyte sequence, e.g. because the buffer filled up within a character.
+    TruncatedMultibyte {
+        /// The length of the valid prefix.
+        valid_up_to: usize,
+    },
+    /// The buffer filled before a newline was seen; the rest of the line is left unread.
+    TooLong {
+        /// The length of the valid prefix, which is the buffer length.
+        valid_up_to: usize,
+    },
+}
+
+/// Represents a checksum mismatch reported by [`BlockingUart::read_with_checksum`](crate::BlockingUart::read_with_checksum).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChecksumError {
+    /// The length of the payload stored in the buffer, without the checksum byte.
+    pub len: usize,
+    /// The checksum computed over the payload.
+    pub expected: u8,
+    /// The checksum byte received.
+    pub actual: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_io::{Error, ErrorKind};
+
+    #[test]
+    fn uart_error_embedded_io() {
+        // Test an infallible result converts with `?`
+        fn forward(res: Result<u8, core::convert::Infallible>) -> Result<u8, UartError> {
+            Ok(res?)
+        }
+        assert_eq!(forward(Ok(1)), Ok(1));
+
+        // Test transient and corrupt data errors map to distinct kinds
+        assert_eq!(UartError::Overrun.kind(), ErrorKind::Interrupted);
+        assert_eq!(UartError::Framing.kind(), ErrorKind::InvalidData);
+        assert_eq!(UartError::Break.kind(), ErrorKind::Other);
+    }
+}
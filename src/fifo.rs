@@ -0,0 +1,168 @@
+#![allow(unused)]
+
+use crate::register::{RbrThrDll, RegisterBlock, UartRegisters};
+use crate::{BlockingUart, ConfigError};
+use core::ops::Deref;
+
+/// A UART with FIFOs of a fixed depth of `FIFO` bytes, known at compile time.
+///
+/// The depth is usually found once during bring-up and then encoded in the type,
+/// so burst buffers can be sized statically and the burst loops have constant bounds.
+/// The default of 16 bytes matches the 16550A.
+/// The wrapped UART must have its FIFOs enabled, and the hardware FIFOs must be at least `FIFO` bytes deep,
+/// otherwise burst writes would overflow the transmitter; bursts are refused with a [`ConfigError`] then.
+pub struct FifoUart<UART, const FIFO: usize = 16> {
+    uart: BlockingUart<UART>,
+}
+
+impl<UART: UartRegisters, const FIFO: usize> FifoUart<UART, FIFO> {
+    /// The FIFO depth in bytes.
+    pub const DEPTH: usize = FIFO;
+
+    /// Creates a new FifoUart from a BlockingUart with FIFOs enabled.
+    pub fn new(uart: BlockingUart<UART>) -> Self {
+        const { assert!(FIFO > 0, "FIFO depth must not be zero") };
+        FifoUart { uart }
+    }
+
+    /// Gets the number of bytes the transmitter can accept without waiting.
+    ///
+    /// With FIFOs enabled, THRE means the whole transmit FIFO is empty,
+    /// so this is either `FIFO` or 0. This is the size of the next chunk a DMA transfer may fill.
+    /// Readiness follows the [`WriteStrategy`](crate::WriteStrategy) of the wrapped UART, as for its writes.
+    pub fn tx_space(&self) -> usize {
+        match self.uart.tx_ready() {
+            true => FIFO,
+            false => 0,
+        }
+    }
+
+    /// Reads up to one FIFO's worth of received data.
+    ///
+    /// Returns the number of bytes actually read,
+    /// or a [`ConfigError`] without reading if the FIFOs do not fit, see [`write_burst`](Self::write_burst).
+    pub fn read_burst(&mut self, buf: &mut [u8; FIFO]) -> Result<usize, ConfigError> {
+        self.check_fifo()?;
+        Ok(self.uart.read(buf))
+    }
+
+    /// Writes up to one FIFO's worth of data if the transmit FIFO is empty.
+    ///
+    /// Readiness is checked once, by THRE or TEMT according to the write strategy, see [`tx_space`](Self::tx_space),
+    /// then up to `FIFO` bytes are written without checking it again.
+    /// Returns the number of bytes actually written, 0 if the transmit FIFO was not empty.
+    /// Returns [`ConfigError::FifoDisabled`] without writing unless FIFOs were enabled and confirmed through the IIR,
+    /// by [`BlockingUart::new`] or [`BlockingUart::enable_fifo`], as the burst would overrun the one-byte holding register.
+    /// Returns [`ConfigError::FifoTooShallow`] if the FIFO the part confirmed holds fewer than `FIFO` bytes,
+    /// e.g. 16 on a 16550A, as the end of the burst would be dropped.
+    pub fn write_burst(&mut self, buf: &[u8]) -> Result<usize, ConfigError> {
+        self.check_fifo()?;
+        let len = buf.len().min(self.tx_space());
+        let uart = self.uart.registers();
+        for &byte in &buf[..len] {
+            unsafe {
+                uart.write_rbr_thr_dll(RbrThrDll::from_transmitter_data(byte));
+            }
+        }
+        Ok(self.uart.count_tx(len))
+    }
+
+    /// Checks that the wrapped BlockingUart has FIFOs of at least `FIFO` bytes enabled, as confirmed by the IIR.
+    #[inline(always)]
+    fn check_fifo(&self) -> Result<(), ConfigError> {
+        if !self.uart.is_fifo_enabled() {
+            return Err(ConfigError::FifoDisabled);
+        }
+        match FIFO <= self.uart.tx_burst() {
+            true => Ok(()),
+            false => Err(ConfigError::FifoTooShallow),
+        }
+    }
+
+    /// Returns a mutable reference to the wrapped BlockingUart.
+    pub fn inner_mut(&mut self) -> &mut BlockingUart<UART> {
+        &mut self.uart
+    }
+
+    /// Consumes the FifoUart, returning the wrapped BlockingUart.
+    pub fn into_inner(self) -> BlockingUart<UART> {
+        self.uart
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockUart, set_lsr};
+    use crate::register::FifoStatus;
+    use crate::{Config, WriteStrategy};
+
+    #[test]
+    fn fifo_uart_burst() {
+        let mock = MockUart::new();
+        let mut uart: FifoUart<_, 4> = FifoUart::new(BlockingUart::new(&mock, Config::new(), true));
+        assert_eq!(FifoUart::<&RegisterBlock, 4>::DEPTH, 4);
+
+        // Test a burst write is limited to the FIFO depth
+        assert_eq!(uart.tx_space(), 4);
+        assert_eq!(uart.write_burst(&[0x5A; 6]), Ok(4));
+        assert_eq!(mock.regs.rbr_thr_dll.read().receiver_data(), 0x5A);
+
+        // Test nothing is written while the transmit FIFO is not empty
+        set_lsr(&mock.regs, 0x01);
+        assert_eq!(uart.tx_space(), 0);
+        assert_eq!(uart.write_burst(&[0x5A; 6]), Ok(0));
+
+        // Test the TEMT strategy holds a burst back while the shift register is busy
+        uart.inner_mut().set_write_strategy(WriteStrategy::Temt);
+        set_lsr(&mock.regs, 0x21);
+        assert_eq!(uart.tx_space(), 0);
+        assert_eq!(uart.write_burst(&[0x5A; 6]), Ok(0));
+        set_lsr(&mock.regs, 0x61);
+        assert_eq!(uart.tx_space(), 4);
+
+        // Test a burst read fills at most one FIFO's worth
+        let mut buf = [0; 4];
+        assert_eq!(uart.read_burst(&mut buf), Ok(4));
+        assert_eq!(buf, [0x5A; 4]);
+
+        // Test bursts are refused without FIFOs
+        let mut uart: FifoUart<_, 4> =
+            FifoUart::new(BlockingUart::new(&mock, Config::new(), false));
+        assert_eq!(uart.write_burst(&[0x5A; 6]), Err(ConfigError::FifoDisabled));
+        assert_eq!(uart.read_burst(&mut buf), Err(ConfigError::FifoDisabled));
+    }
+
+    #[test]
+    fn fifo_uart_burst_too_deep() {
+        let mock = MockUart::new();
+
+        // Test a depth beyond the 16 bytes confirmed is refused
+        let mut uart: FifoUart<_, 64> =
+            FifoUart::new(BlockingUart::new(&mock, Config::new(), true));
+        assert_eq!(
+            uart.write_burst(&[0x5A; 64]),
+            Err(ConfigError::FifoTooShallow)
+        );
+        let mut buf = [0; 64];
+        assert_eq!(uart.read_burst(&mut buf), Err(ConfigError::FifoTooShallow));
+
+        // Test the confirmed depth itself is accepted
+        let mut uart: FifoUart<_, 16> = FifoUart::new(uart.into_inner());
+        assert_eq!(uart.write_burst(&[0x5A; 16]), Ok(16));
+    }
+
+    #[test]
+    fn fifo_uart_burst_unconfirmed() {
+        let mock = MockUart::new();
+        mock.set_fifo_status(FifoStatus::Disabled);
+
+        // Test FIFOs set in the FCR but reported as disabled by the IIR, as on a 16450, are refused
+        let mut uart: FifoUart<_, 4> = FifoUart::new(BlockingUart::new(&mock, Config::new(), true));
+        assert!(mock.fcr().is_fifo_enable_set());
+        assert_eq!(mock.read_iir_fcr().fifo_status(), FifoStatus::Disabled);
+        assert_eq!(uart.write_burst(&[0x5A; 6]), Err(ConfigError::FifoDisabled));
+        let mut buf = [0; 4];
+        assert_eq!(uart.read_burst(&mut buf), Err(ConfigError::FifoDisabled));
+    }
+}
@@ -0,0 +1,93 @@
+use core::fmt;
+
+/// The number of bytes per line used by [`hexdump`].
+pub const HEXDUMP_WIDTH: usize = 16;
+
+/// Writes a hex dump of `data`, 16 bytes per line.
+///
+/// Each line holds the offset, the bytes in hex and an ASCII gutter,
+/// with non-printable bytes shown as `.`:
+///
+/// ```text
+/// 00000000  48 65 6c 6c 6f 0d 0a                             |Hello..|
+/// ```
+///
+/// Any [`fmt::Write`] works, including [`BlockingUart`](crate::BlockingUart).
+/// Nothing is allocated.
+pub fn hexdump(w: &mut impl fmt::Write, data: &[u8]) -> fmt::Result {
+    hexdump_with_width(w, data, HEXDUMP_WIDTH)
+}
+
+/// Writes a hex dump of `data` with `width` bytes per line.
+///
+/// A `width` of 0 is treated as 1. See [`hexdump`] for the format.
+pub fn hexdump_with_width(w: &mut impl fmt::Write, data: &[u8], width: usize) -> fmt::Result {
+    let width = width.max(1);
+    for (line, chunk) in data.chunks(width).enumerate() {
+        write!(w, "{:08x} ", line * width)?;
+        for byte in chunk {
+            write!(w, " {byte:02x}")?;
+        }
+        for _ in chunk.len()..width {
+            w.write_str("   ")?;
+        }
+        w.write_str("  |")?;
+        for &byte in chunk {
+            let c = match byte.is_ascii_graphic() || byte == b' ' {
+                true => byte as char,
+                false => '.',
+            };
+            w.write_char(c)?;
+        }
+        w.write_str("|\n")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Buf {
+        data: [u8; 256],
+        len: usize,
+    }
+
+    impl fmt::Write for Buf {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            self.data
+                .get_mut(self.len..end)
+                .ok_or(fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    impl Buf {
+        fn as_str(&self) -> &str {
+            core::str::from_utf8(&self.data[..self.len]).unwrap()
+        }
+    }
+
+    #[test]
+    fn hexdump_format() {
+        let mut buf = Buf {
+            data: [0; 256],
+            len: 0,
+        };
+
+        // Test a partial last line is padded so the gutters line up
+        hexdump_with_width(&mut buf, b"Hello\r\n", 4).unwrap();
+        assert_eq!(
+            buf.as_str(),
+            "00000000  48 65 6c 6c  |Hell|\n00000004  6f 0d 0a     |o..|\n"
+        );
+
+        // Test empty data writes nothing
+        buf.len = 0;
+        hexdump(&mut buf, &[]).unwrap();
+        assert_eq!(buf.as_str(), "");
+    }
+}
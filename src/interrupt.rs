@@ -0,0 +1,256 @@
+use crate::register::UartRegisters;
+use crate::{BlockingUart, BufferedUart, OverflowPolicy};
+
+/// An interrupt-driven UART transmitter with a ring buffer of `N` bytes.
+///
+/// Thread code queues data with [`try_enqueue`](Self::try_enqueue), and the transmitter
+/// holding register empty interrupt moves it to the hardware in [`on_thre_interrupt`](Self::on_thre_interrupt).
+/// The interrupt is enabled by each enqueue that stores data, which raises it at once when the
+/// transmitter is idle, and disabled by the handler when the ring runs empty.
+///
+/// Both sides take `&mut self`, so this value must be shared between thread code and the handler
+/// behind a lock that masks the interrupt, typically a `critical_section::Mutex<RefCell<_>>` in a static:
+/// the thread side enqueues inside a critical section, and the handler registered for the UART interrupt
+/// borrows the same value and calls [`on_thre_interrupt`](Self::on_thre_interrupt).
+/// On PC-compatible boards OUT2 must be set for the interrupt to reach the CPU,
+/// see [`BlockingUart::enable_interrupts`].
+pub struct InterruptTx<UART, const N: usize> {
+    uart: BlockingUart<UART>,
+    ring: [u8; N],
+    head: usize,
+    len: usize,
+}
+
+impl<UART: UartRegisters, const N: usize> InterruptTx<UART, N> {
+    /// Creates a new InterruptTx from a BlockingUart, with an empty ring.
+    pub fn new(uart: BlockingUart<UART>) -> Self {
+        const { assert!(N > 0, "ring size must not be zero") };
+        InterruptTx {
+            uart,
+            ring: [0; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Queues as much of `data` as fits in the ring, without blocking.
+    ///
+    /// Returns the number of bytes queued. If any were, the transmitter holding register
+    /// empty interrupt is enabled, so the handler starts or keeps draining the ring.
+    pub fn try_enqueue(&mut self, data: &[u8]) -> usize {
+        let count = data.len().min(N - self.len);
+        for &byte in &data[..count] {
+            self.ring[(self.head + self.len) % N] = byte;
+            self.len += 1;
+        }
+        if count > 0 {
+            self.set_transmitter_empty_interrupt(true);
+        }
+        count
+    }
+
+    /// Handles the transmitter holding register empty interrupt.
+    ///
+    /// Moves queued bytes to the transmitter until it is full or the ring is empty.
+    /// Once the ring is empty the interrupt is disabled, as it would otherwise keep firing.
+    pub fn on_thre_interrupt(&mut self) {
+        while self.len > 0 {
+            let end = (self.head + self.len).min(N);
+            let written = self.uart.write(&self.ring[self.head..end]);
+            if written == 0 {
+                return;
+            }
+            self.head = (self.head + written) % N;
+            self.len -= written;
+        }
+        self.set_transmitter_empty_interrupt(false);
+    }
+
+    /// Gets the number of bytes waiting in the ring.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the ring is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Consumes the InterruptTx, returning the wrapped BlockingUart.
+    ///
+    /// Bytes still in the ring are discarded, and the transmitter holding register empty interrupt is disabled.
+    pub fn into_inner(self) -> BlockingUart<UART> {
+        self.set_transmitter_empty_interrupt(false);
+        self.uart
+    }
+
+    fn set_transmitter_empty_interrupt(&self, enable: bool) {
+        let uart = self.uart.registers();
+        let ier = match enable {
+            true => uart.read_ier_dlh().enable_transmitter_empty_interrupt(),
+            false => uart.read_ier_dlh().disable_transmitter_empty_interrupt(),
+        };
+        unsafe {
+            uart.write_ier_dlh(ier);
+        }
+    }
+}
+
+/// An interrupt-driven UART receiver with a ring buffer of `N` bytes.
+///
+/// The received data available interrupt, which also covers the character timeout,
+/// is enabled on creation. Its handler calls [`on_rx_interrupt`](Self::on_rx_interrupt) to drain
+/// the hardware FIFO into the ring, and thread code consumes the ring with [`read`](Self::read).
+/// As with [`InterruptTx`], both sides take `&mut self`, so this value must be shared behind a lock
+/// that masks the interrupt, typically a `critical_section::Mutex<RefCell<_>>` in a static.
+///
+/// When the ring is full, the [`OverflowPolicy`] applies as for [`BufferedUart`].
+/// With [`OverflowPolicy::Backpressure`], RTS is deasserted at the high watermark and new bytes stay
+/// in the hardware FIFO. That would keep the interrupt asserted, so it is disabled while the ring
+/// is full and enabled again by the next [`read`](Self::read) that makes room; the peer must honor RTS,
+/// or the hardware FIFO overruns in the meantime.
+pub struct InterruptRx<UART, const N: usize> {
+    rx: BufferedUart<UART, N>,
+    policy: OverflowPolicy,
+}
+
+impl<UART: UartRegisters, const N: usize> InterruptRx<UART, N> {
+    /// Creates a new InterruptRx with the specified overflow policy, enabling the received data available interrupt.
+    ///
+    /// On PC-compatible boards OUT2 must be set for the interrupt to reach the CPU,
+    /// see [`BlockingUart::enable_interrupts`].
+    pub fn new(uart: BlockingUart<UART>, policy: OverflowPolicy) -> Self {
+        let rx = InterruptRx {
+            rx: BufferedUart::new(uart, policy),
+            policy,
+        };
+        rx.set_received_data_interrupt(true);
+        rx
+    }
+
+    /// Handles the received data available and character timeout interrupts.
+    ///
+    /// Moves received bytes into the ring until data ready clears, or until the ring is full
+    /// under [`OverflowPolicy::Backpressure`], in which case the interrupt is disabled.
+    /// Returns the number of bytes stored.
+    pub fn on_rx_interrupt(&mut self) -> usize {
+        let count = self.rx.poll();
+        if self.policy == OverflowPolicy::Backpressure && self.rx.available() == N {
+            self.set_received_data_interrupt(false);
+        }
+        count
+    }
+
+    /// Reads buffered data into the provided buffer.
+    ///
+    /// Bytes already in the hardware FIFO are taken as well. If the interrupt was disabled
+    /// because the ring was full, it is enabled again once there is room.
+    /// Returns the number of bytes actually read.
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        let count = self.rx.read(buf);
+        if self.policy == OverflowPolicy::Backpressure && self.rx.available() < N {
+            self.set_received_data_interrupt(true);
+        }
+        count
+    }
+
+    /// Returns the number of bytes waiting in the ring buffer.
+    pub fn available(&self) -> usize {
+        self.rx.available()
+    }
+
+    /// Returns the number of received bytes dropped because the ring buffer was full.
+    pub fn dropped(&self) -> usize {
+        self.rx.dropped()
+    }
+
+    /// Consumes the InterruptRx, returning the wrapped BlockingUart.
+    ///
+    /// Bytes still in the ring are discarded, and the received data available interrupt is disabled.
+    pub fn into_inner(self) -> BlockingUart<UART> {
+        self.set_received_data_interrupt(false);
+        self.rx.into_inner()
+    }
+
+    fn set_received_data_interrupt(&self, enable: bool) {
+        let uart = self.rx.registers();
+        let ier = match enable {
+            true => uart
+                .read_ier_dlh()
+                .enable_received_data_available_interrupt(),
+            false => uart
+                .read_ier_dlh()
+                .disable_received_data_available_interrupt(),
+        };
+        unsafe {
+            uart.write_ier_dlh(ier);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use crate::mock::{mock_uart, set_lsr};
+
+    #[test]
+    fn interrupt_tx_enqueue_and_drain() {
+        let mock = mock_uart();
+        let mut tx = InterruptTx::<_, 2>::new(BlockingUart::new(&mock, Config::new(), false));
+
+        // Test only what fits is queued, and the interrupt is enabled
+        assert_eq!(tx.try_enqueue(&[1, 2, 3]), 2);
+        assert_eq!(tx.len(), 2);
+        assert!(mock.ier_dlh.read().is_transmitter_empty_interrupt_enabled());
+
+        // Test a full transmitter keeps the data queued
+        set_lsr(&mock, 0x00);
+        tx.on_thre_interrupt();
+        assert_eq!(tx.len(), 2);
+
+        // Test the handler drains the ring, then disables the interrupt
+        set_lsr(&mock, 0x60);
+        tx.on_thre_interrupt();
+        assert!(tx.is_empty());
+        assert_eq!(mock.rbr_thr_dll.read().receiver_data(), 2);
+        assert!(!mock.ier_dlh.read().is_transmitter_empty_interrupt_enabled());
+
+        // Test the ring wraps around
+        assert_eq!(tx.try_enqueue(&[3, 4]), 2);
+        tx.on_thre_interrupt();
+        assert_eq!(mock.rbr_thr_dll.read().receiver_data(), 4);
+    }
+
+    #[test]
+    fn interrupt_rx_backpressure() {
+        let mock = mock_uart();
+        let uart = BlockingUart::new(&mock, Config::new(), false);
+        let mut rx = InterruptRx::<_, 4>::new(uart, OverflowPolicy::Backpressure);
+        assert!(
+            mock.ier_dlh
+                .read()
+                .is_received_data_available_interrupt_enabled()
+        );
+
+        // Test a full ring disables the interrupt, leaving data in the hardware
+        set_lsr(&mock, 0x61);
+        assert_eq!(rx.on_rx_interrupt(), 4);
+        assert!(
+            !mock
+                .ier_dlh
+                .read()
+                .is_received_data_available_interrupt_enabled()
+        );
+
+        // Test making room enables it again
+        set_lsr(&mock, 0x60);
+        assert_eq!(rx.read(&mut [0; 2]), 2);
+        assert_eq!(rx.available(), 2);
+        assert!(
+            mock.ier_dlh
+                .read()
+                .is_received_data_available_interrupt_enabled()
+        );
+    }
+}
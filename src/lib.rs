@@ -1,8 +1,24 @@
 #![no_std]
 #![allow(unused)]
 
+#[cfg(feature = "async")]
+mod asynch;
 mod blocking;
+mod bridge;
+mod buffered;
+mod checksum;
+mod designware;
+mod error;
+mod fifo;
+mod hexdump;
+mod interrupt;
+mod lpc;
+#[cfg(test)]
+mod mock;
+mod polarity;
 mod register;
+mod rs485;
+mod wait;
 
 
 
@@ -13,17 +29,38 @@ pub mod prelude {
 }
 
 
-pub use crate::blocking::BlockingUart;
+#[cfg(feature = "async")]
+pub use crate::asynch::AsyncTx;
+pub use crate::blocking::{
+    BerResult, BlockingUart, Capabilities, ConfigGuard, EchoMismatch, ErrorCheckOrder, LineHealth,
+    ReadLine, UartKind, WriteStrategy,
+};
+pub use crate::bridge::Bridge;
+pub use crate::buffered::{BufferedUart, OverflowPolicy};
+pub use crate::checksum::ChecksumAlgo;
+pub use crate::designware::*;
+pub use crate::error::{ChecksumError, ConfigError, UartError, Utf8LineError};
+pub use crate::fifo::FifoUart;
+pub use crate::hexdump::{HEXDUMP_WIDTH, hexdump, hexdump_with_width};
+pub use crate::interrupt::{InterruptRx, InterruptTx};
+pub use crate::lpc::*;
+pub use crate::polarity::PolarityControl;
 pub use crate::register::*;
+pub use crate::rs485::Rs485;
+pub use crate::wait::{Spin, WaitStrategy, Wfi};
 
 /// Configuration struct for UART settings.
 ///
 /// This struct contains all configurable parameters for the UART interface.
 /// Including divisor, parity mode, stop bits and word length settings.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Config {
     /// The divisor value for baud rate generation.
-    pub divisor: u16,
+    ///
+    /// `None` leaves the divisor latch untouched when the configuration is applied,
+    /// keeping the baud rate already programmed, e.g. by a bootloader.
+    pub divisor: Option<u16>,
     /// The parity checking mode.
     pub parity_mode: ParityMode,
     /// Number of stop bits.
@@ -42,7 +79,7 @@ impl Config {
     /// - 8 bits word length.
     pub fn new() -> Self {
         Self {
-            divisor: 0,
+            divisor: None,
             parity_mode: ParityMode::None,
             stop_bits: StopBits::Bit1,
             word_length: WordLength::Bits8,
@@ -51,7 +88,15 @@ impl Config {
 
     /// Sets the divisor value.
     pub fn set_divisor(mut self, divisor: u16) -> Self {
-        self.divisor = divisor;
+        self.divisor = Some(divisor);
+        self
+    }
+
+    /// Sets the divisor for the baud rate, given the UART input clock.
+    ///
+    /// The divisor is rounded to the nearest value, see [`baud_to_divisor`].
+    pub fn set_baud_rate(mut self, clock: Hertz, baud: Baud) -> Self {
+        self.divisor = Some(baud_to_divisor(clock.0, baud.0));
         self
     }
 
@@ -72,10 +117,244 @@ impl Config {
         self.word_length = word_length;
         self
     }
+
+    /// Gets the stop bits actually sent with this word length.
+    pub const fn effective_stop_bits(&self) -> EffectiveStopBits {
+        self.stop_bits.effective(self.word_length)
+    }
+
+    /// Gets the number of bits in one frame: start bit, data bits, parity bit and stop bits.
+    ///
+    /// With 1.5 stop bits the half bit is rounded up, so this never underestimates the frame;
+    /// use [`frame_duration_ns`](Self::frame_duration_ns) for the exact length.
+    pub const fn frame_bits(&self) -> u32 {
+        self.frame_half_bits().div_ceil(2)
+    }
+
+    /// Gets the duration of one frame in nanoseconds, given the UART input clock in hertz.
+    ///
+    /// One bit lasts `16 * divisor / clock_hz` seconds, and 1.5 stop bits count as such.
+    /// Returns `None` if the divisor is unset or 0, or `clock_hz` is 0, as the baud rate is then unknown.
+    /// The result saturates at `u32::MAX`, about 4.3 seconds.
+    pub const fn frame_duration_ns(&self, clock_hz: u32) -> Option<u32> {
+        let divisor = match self.divisor {
+            Some(divisor) if divisor != 0 && clock_hz != 0 => divisor as u64,
+            _ => return None,
+        };
+        let ns = self.frame_half_bits() as u64 * 8 * divisor * 1_000_000_000 / clock_hz as u64;
+        if ns > u32::MAX as u64 {
+            Some(u32::MAX)
+        } else {
+            Some(ns as u32)
+        }
+    }
+
+    /// Gets the number of half bits in one frame, so 1.5 stop bits stay exact.
+    pub(crate) const fn frame_half_bits(&self) -> u32 {
+        let data = match self.word_length {
+            WordLength::Bits5 => 5,
+            WordLength::Bits6 => 6,
+            WordLength::Bits7 => 7,
+            WordLength::Bits8 => 8,
+        };
+        let parity = match self.parity_mode {
+            ParityMode::None => 0,
+            _ => 1,
+        };
+        let stop_half_bits = match self.effective_stop_bits() {
+            EffectiveStopBits::One => 2,
+            EffectiveStopBits::OneAndHalf => 3,
+            EffectiveStopBits::Two => 4,
+        };
+        2 * (1 + data + parity) + stop_half_bits
+    }
+
+    /// Checks the configuration for settings that are invalid or likely unintended.
+    ///
+    /// The 16550 has a single Line Control Register for both directions, so the receiver and
+    /// the transmitter always share the word length, parity and stop bits held here;
+    /// a link that needs different framing per direction cannot be served by one UART.
+    /// Parity modes and word lengths that do not map to the register are ruled out by the types,
+    /// see the [`TryFrom<u8>`] implementations for decoding raw values. The rules checked, in order, are:
+    ///
+    /// 1. A divisor of 0 gives [`ConfigError::ZeroDivisor`]: the baud rate generator
+    ///    does not run, or runs at a part-specific rate.
+    /// 2. [`StopBits::Bits2OrBits1_5`] with [`WordLength::Bits5`] gives [`ConfigError::OneAndHalfStopBits`],
+    ///    as the hardware then sends 1.5 stop bits instead of 2.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.divisor == Some(0) {
+            return Err(ConfigError::ZeroDivisor);
+        }
+        if self.effective_stop_bits() == EffectiveStopBits::OneAndHalf {
+            return Err(ConfigError::OneAndHalfStopBits);
+        }
+        Ok(())
+    }
+
+    /// Gets the register writes that apply this configuration, in order.
+    ///
+    /// This is the sequence [`BlockingUart::new`] uses to set the divisor and line settings,
+    /// so initialization can be replayed without a live UART, e.g. from a reset handler
+    /// or another core, or checked in tests. If the divisor is set, DLAB is set in the LCR,
+    /// the divisor latch is written low byte first, and DLAB is cleared by the final LCR write,
+    /// which also applies parity, stop bits and word length. Without a divisor only that LCR write remains.
+    /// Unlike `new`, no register is read, so the break control bit is cleared rather than preserved.
+    /// `new` then writes the FCR and IER, which this configuration does not cover.
+    pub fn init_sequence(&self) -> InitSequence {
+        let lcr = with_parity_mode(Lcr::default(), self.parity_mode)
+            .set_stop_bits(self.stop_bits)
+            .set_word_length(self.word_length);
+        let mut seq = InitSequence {
+            writes: [(RegOffset::Lcr, 0); 4],
+            len: 0,
+        };
+        if let Some(divisor) = self.divisor {
+            let [low, high] = divisor.to_le_bytes();
+            let dlab = lcr.enable_divisor_latch_access();
+            seq.push(RegOffset::Lcr, dlab.bits() as u8);
+            seq.push(RegOffset::RbrThrDll, low);
+            seq.push(RegOffset::IerDlh, high);
+        }
+        seq.push(RegOffset::Lcr, lcr.bits() as u8);
+        seq
+    }
+}
+
+/// The register writes that apply a [`Config`], returned by [`Config::init_sequence`].
+///
+/// Dereferences to a slice of register and value pairs, in the order they must be written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InitSequence {
+    writes: [(RegOffset, u8); 4],
+    len: usize,
+}
+
+impl InitSequence {
+    fn push(&mut self, reg: RegOffset, val: u8) {
+        self.writes[self.len] = (reg, val);
+        self.len += 1;
+    }
+}
+
+impl core::ops::Deref for InitSequence {
+    type Target = [(RegOffset, u8)];
+
+    fn deref(&self) -> &Self::Target {
+        &self.writes[..self.len]
+    }
+}
+
+impl Default for Config {
+    /// Same as [`Config::new`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A frequency in hertz, such as the UART input clock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Hertz(pub u32);
+
+impl From<u32> for Hertz {
+    fn from(val: u32) -> Self {
+        Hertz(val)
+    }
+}
+
+/// A baud rate in symbols per second.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Baud(pub u32);
+
+impl From<u32> for Baud {
+    fn from(val: u32) -> Self {
+        Baud(val)
+    }
+}
+
+/// UART parameters in the form a devicetree node gives them.
+///
+/// A `ns16550a`-compatible node describes its UART with `clock-frequency`, `reg-shift`, `reg-io-width`
+/// and optionally `current-speed`; [`BlockingUart::from_params`] builds a UART from these and the node's base address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UartParams {
+    /// The UART input clock, from `clock-frequency`.
+    pub clock_frequency: Hertz,
+    /// The log2 of the distance in bytes between two registers, from `reg-shift`; 0 to 2.
+    pub reg_shift: u8,
+    /// The width in bytes of each register access, from `reg-io-width`; 1 or 4.
+    pub reg_io_width: u8,
+    /// The baud rate to program, from `current-speed`.
+    ///
+    /// `None` keeps the divisor of the configuration passed along.
+    pub current_speed: Option<Baud>,
+}
+
+impl UartParams {
+    /// Checks the parameters, returning the access width.
+    ///
+    /// Returns [`ConfigError::InvalidRegShift`] if `reg_shift` is above 2,
+    /// and [`ConfigError::InvalidRegIoWidth`] if `reg_io_width` is neither 1 nor 4.
+    pub fn validate(&self) -> Result<RegIoWidth, ConfigError> {
+        if self.reg_shift > 2 {
+            return Err(ConfigError::InvalidRegShift);
+        }
+        match self.reg_io_width {
+            1 => Ok(RegIoWidth::U8),
+            4 => Ok(RegIoWidth::U32),
+            _ => Err(ConfigError::InvalidRegIoWidth),
+        }
+    }
+}
+
+/// Computes the divisor for a baud rate, given the UART input clock in hertz.
+///
+/// The UART samples at 16 times the baud rate, so the divisor is `clock_hz / (16 * baud)`,
+/// rounded to the nearest integer and clamped to the range `1..=u16::MAX`.
+/// Returns 0 if `baud` is 0.
+pub const fn baud_to_divisor(clock_hz: u32, baud: u32) -> u16 {
+    if baud == 0 {
+        return 0;
+    }
+    let sample_rate = 16 * baud as u64;
+    let divisor = (clock_hz as u64 + sample_rate / 2) / sample_rate;
+    if divisor == 0 {
+        1
+    } else if divisor > u16::MAX as u64 {
+        u16::MAX
+    } else {
+        divisor as u16
+    }
+}
+
+/// Computes the baud rate produced by a divisor, given the UART input clock in hertz.
+///
+/// Returns 0 if `divisor` is 0.
+pub const fn divisor_to_baud(clock_hz: u32, divisor: u16) -> u32 {
+    if divisor == 0 {
+        return 0;
+    }
+    clock_hz / (16 * divisor as u32)
+}
+
+/// Computes the duration of one bit in nanoseconds produced by a divisor, given the UART input clock in hertz.
+///
+/// One bit lasts `16 * divisor / clock_hz` seconds. Returns 0 if `divisor` or `clock_hz` is 0,
+/// and saturates at `u32::MAX`.
+pub const fn bit_duration_ns(clock_hz: u32, divisor: u16) -> u32 {
+    if clock_hz == 0 {
+        return 0;
+    }
+    let ns = 16 * divisor as u64 * 1_000_000_000 / clock_hz as u64;
+    if ns > u32::MAX as u64 {
+        u32::MAX
+    } else {
+        ns as u32
+    }
 }
 
 /// Represents different parity checking modes for UART communication.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ParityMode {
     /// No parity checking.
     None,
@@ -83,48 +362,323 @@ pub enum ParityMode {
     Odd,
     /// Even parity checking.
     Even,
-    /// Force parity bit high.
+    /// Force parity bit high, also known as mark parity, see [`ParityMode::Mark`].
     High,
-    /// Force parity bit low.
+    /// Force parity bit low, also known as space parity, see [`ParityMode::Space`].
     Low,
 }
 
-/// Gets the current divisor value from UART registers.
-pub(crate) fn divisor(uart: &RegisterBlock) -> u16 {
-    let lcr = uart.lcr.read();
+#[allow(non_upper_case_globals)]
+impl ParityMode {
+    /// Mark parity: the parity bit is always 1.
+    ///
+    /// This is the industry-standard name for [`ParityMode::High`], and the same value,
+    /// so it may be used in patterns too.
+    pub const Mark: ParityMode = ParityMode::High;
+
+    /// Space parity: the parity bit is always 0.
+    ///
+    /// This is the industry-standard name for [`ParityMode::Low`], and the same value.
+    pub const Space: ParityMode = ParityMode::Low;
+}
+
+impl TryFrom<u8> for ParityMode {
+    type Error = DecodeError;
+
+    /// Decodes the parity mode from the LCR parity fields.
+    ///
+    /// The value holds the parity enable, even parity select and stick parity bits
+    /// as bits 0, 1 and 2, i.e. LCR bits 3 to 5 shifted down.
+    /// Values with parity disabled but other parity bits set are rejected as reserved.
+    fn try_from(val: u8) -> Result<Self, Self::Error> {
+        match val {
+            0b000 => Ok(ParityMode::None),
+            0b001 => Ok(ParityMode::Odd),
+            0b011 => Ok(ParityMode::Even),
+            0b101 => Ok(ParityMode::High),
+            0b111 => Ok(ParityMode::Low),
+            _ => Err(DecodeError::InvalidParityMode(val)),
+        }
+    }
+}
+
+/// Snapshot of the Modem Status Register.
+///
+/// Contains the current state of the modem input lines,
+/// and whether each of them changed since the previous read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct ModemStatus {
+    /// Clear to Send input is active.
+    pub clear_to_send: bool,
+    /// Data Set Ready input is active.
+    pub data_set_ready: bool,
+    /// Ring Indicator input is active.
+    pub ring_indicator: bool,
+    /// Data Carrier Detect input is active.
+    pub data_carrier_detect: bool,
+    /// Clear to Send changed since the last read.
+    pub delta_clear_to_send: bool,
+    /// Data Set Ready changed since the last read.
+    pub delta_data_set_ready: bool,
+    /// Ring Indicator went inactive since the last read.
+    pub trailing_edge_of_ring_indicator: bool,
+    /// Data Carrier Detect changed since the last read.
+    pub delta_data_carrier_detect: bool,
+}
+
+impl From<Msr> for ModemStatus {
+    fn from(msr: Msr) -> Self {
+        Self {
+            clear_to_send: msr.clear_to_send(),
+            data_set_ready: msr.data_set_ready(),
+            ring_indicator: msr.ring_indicator(),
+            data_carrier_detect: msr.data_carrier_detect(),
+            delta_clear_to_send: msr.delta_clear_to_send(),
+            delta_data_set_ready: msr.delta_data_set_ready(),
+            trailing_edge_of_ring_indicator: msr.trailing_edge_of_ring_indicator(),
+            delta_data_carrier_detect: msr.delta_data_carrier_detect(),
+        }
+    }
+}
+
+impl ModemStatus {
+    /// Checks if any modem input changed since the last read.
+    pub const fn has_changed(&self) -> bool {
+        self.delta_clear_to_send
+            || self.delta_data_set_ready
+            || self.trailing_edge_of_ring_indicator
+            || self.delta_data_carrier_detect
+    }
+}
+
+/// Full configuration of a UART, including FIFO, flow control and interrupt state.
+///
+/// Returned by [`BlockingUart::extended_config`].
+/// The FCR is write-only, so the FIFO fields come from the software shadow kept by [`BlockingUart`];
+/// all other fields are read from the live registers.
+///
+/// The default value has every feature disabled and the divisor unset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct ExtendedConfig {
+    /// Baud rate divisor and line settings, from DLL, DLH and LCR.
+    pub config: Config,
+    /// FIFOs enabled, from the FCR shadow.
+    pub fifo_enabled: bool,
+    /// 64-byte FIFO mode of the 16750, from the FCR shadow.
+    pub fifo_64byte: bool,
+    /// Receiver FIFO trigger level, from the FCR shadow.
+    pub receiver_trigger_level: ReceiverTriggerLevel,
+    /// Auto flow control enabled, from MCR.
+    pub auto_flow_control: bool,
+    /// Enabled interrupt sources, from IER.
+    pub interrupt_mask: InterruptMask,
+    /// Data Terminal Ready output, from MCR.
+    pub data_terminal_ready: bool,
+    /// Request to Send output, from MCR.
+    pub request_to_send: bool,
+    /// OUT1 output, from MCR.
+    pub out1: bool,
+    /// OUT2 output, from MCR.
+    pub out2: bool,
+    /// Loopback mode, from MCR.
+    pub loop_back: bool,
+}
+
+/// Set of UART interrupt sources, as enabled in the Interrupt Enable Register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct InterruptMask {
+    /// Received data available, including the character timeout.
+    pub received_data_available: bool,
+    /// Transmitter holding register empty.
+    pub transmitter_empty: bool,
+    /// Receiver line status.
+    pub receiver_line_status: bool,
+    /// Modem status.
+    pub modem_status: bool,
+}
+
+impl From<IerDlh> for InterruptMask {
+    fn from(ier: IerDlh) -> Self {
+        Self {
+            received_data_available: ier.is_received_data_available_interrupt_enabled(),
+            transmitter_empty: ier.is_transmitter_empty_interrupt_enabled(),
+            receiver_line_status: ier.is_receiver_line_status_interrupt_enabled(),
+            modem_status: ier.is_modem_status_interrupt_enabled(),
+        }
+    }
+}
+
+/// Gets the enabled interrupts from UART registers.
+///
+/// The IER shares its address with DLH, so DLAB is cleared for the read and LCR restored afterwards.
+pub(crate) fn interrupt_mask(uart: &impl UartRegisters) -> InterruptMask {
+    without_dlab(uart, |uart| InterruptMask::from(uart.read_ier_dlh()))
+}
+
+/// Sets the enabled interrupts in UART registers.
+///
+/// As for [`interrupt_mask`], DLAB is cleared for the write and LCR restored afterwards.
+pub(crate) fn set_interrupt_mask(uart: &impl UartRegisters, mask: InterruptMask) {
+    without_dlab(uart, |uart| {
+        let ier = uart.read_ier_dlh();
+        let ier = match mask.received_data_available {
+            true => ier.enable_received_data_available_interrupt(),
+            false => ier.disable_received_data_available_interrupt(),
+        };
+        let ier = match mask.transmitter_empty {
+            true => ier.enable_transmitter_empty_interrupt(),
+            false => ier.disable_transmitter_empty_interrupt(),
+        };
+        let ier = match mask.receiver_line_status {
+            true => ier.enable_receiver_line_status_interrupt(),
+            false => ier.disable_receiver_line_status_interrupt(),
+        };
+        let ier = match mask.modem_status {
+            true => ier.enable_modem_status_interrupt(),
+            false => ier.disable_modem_status_interrupt(),
+        };
+        unsafe {
+            uart.write_ier_dlh(ier);
+        }
+    })
+}
+
+/// Snapshot of the Line Status Register.
+///
+/// Reading the LSR clears its error bits, so each snapshot reports the errors seen since the previous read.
+/// During a received break every data bit and the stop bit are 0, which also trips the framing error bit;
+/// a snapshot with `break_interrupt` set therefore never reports `framing_error`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct LineStatus {
+    /// Received data is ready to be read.
+    pub data_ready: bool,
+    /// A received character was lost because the receiver buffer was full.
+    pub overrun_error: bool,
+    /// The character at the front of the receiver had a parity error.
+    pub parity_error: bool,
+    /// The character at the front of the receiver had no valid stop bit.
+    pub framing_error: bool,
+    /// A break condition was received.
+    pub break_interrupt: bool,
+    /// The transmit holding register or FIFO is empty.
+    pub transmitter_holding_empty: bool,
+    /// Both the transmit holding register or FIFO and the shift register are empty.
+    pub transmitter_empty: bool,
+    /// At least one character in the receiver FIFO has an error; always false when FIFOs are disabled.
+    pub fifo_error: bool,
+}
+
+impl From<Lsr> for LineStatus {
+    fn from(lsr: Lsr) -> Self {
+        Self {
+            data_ready: lsr.is_data_ready(),
+            overrun_error: lsr.is_overrun_error(),
+            parity_error: lsr.is_parity_error(),
+            framing_error: lsr.is_framing_error() && !lsr.is_broken(),
+            break_interrupt: lsr.is_broken(),
+            transmitter_holding_empty: lsr.is_transmitter_fifo_empty(),
+            transmitter_empty: lsr.is_transmitter_empty(),
+            fifo_error: lsr.is_receiver_fifo_error(),
+        }
+    }
+}
+
+impl LineStatus {
+    /// Gets the most significant receiver error in this snapshot, if any.
+    ///
+    /// See [`UartError::from_line_status`] for the order of precedence.
+    pub const fn error(&self) -> Option<UartError> {
+        if self.break_interrupt {
+            Some(UartError::Break)
+        } else if self.framing_error {
+            Some(UartError::Framing)
+        } else if self.parity_error {
+            Some(UartError::Parity)
+        } else if self.overrun_error {
+            Some(UartError::Overrun)
+        } else {
+            None
+        }
+    }
+}
+
+/// Runs a closure with DLAB set, so that it reaches the registers aliased behind it.
+///
+/// On the accessor passed to the closure the first two registers are the divisor latch,
+/// and FCR writes reach the 16750 extensions. See [`with_lcr`] for how LCR is restored.
+pub(crate) fn with_dlab<U: UartRegisters, R>(uart: &U, f: impl FnOnce(&U) -> R) -> R {
+    with_lcr(uart, Lcr::enable_divisor_latch_access, f)
+}
+
+/// Runs a closure with DLAB cleared, so that it reaches RBR/THR and IER.
+pub(crate) fn without_dlab<U: UartRegisters, R>(uart: &U, f: impl FnOnce(&U) -> R) -> R {
+    with_lcr(uart, Lcr::disable_divisor_latch_access, f)
+}
+
+/// Runs a closure with a temporarily changed LCR.
+///
+/// If `change` alters the LCR, the original value is written back once the closure returns,
+/// or if it panics, so DLAB is never left in the wrong state by mistake.
+/// The closure must not write the LCR itself, as that value would be overwritten.
+fn with_lcr<U: UartRegisters, R>(
+    uart: &U,
+    change: impl FnOnce(Lcr) -> Lcr,
+    f: impl FnOnce(&U) -> R,
+) -> R {
+    /// Writes the saved LCR back when dropped.
+    struct RestoreLcr<'a, U: UartRegisters>(&'a U, Lcr);
+
+    impl<U: UartRegisters> Drop for RestoreLcr<'_, U> {
+        fn drop(&mut self) {
+            unsafe {
+                self.0.write_lcr(self.1);
+            }
+            write_volatile_barrier();
+        }
+    }
+
+    let lcr = uart.read_lcr();
+    let changed = change(lcr);
+    if changed == lcr {
+        return f(uart);
+    }
     unsafe {
-        uart.lcr.write(lcr.enable_divisor_latch_access());
+        uart.write_lcr(changed);
     }
-    let dll = uart.rbr_thr_dll.read().divisor_latch_low_byte();
-    let dlh = uart.ier_dlh.read().divisor_latch_high_byte();
-    u16::from_le_bytes([dll, dlh])
+    write_volatile_barrier();
+    let _restore = RestoreLcr(uart, lcr);
+    f(uart)
+}
+
+/// Gets the current divisor value from UART registers.
+pub(crate) fn divisor(uart: &impl UartRegisters) -> u16 {
+    with_dlab(uart, |uart| {
+        let dll = uart.read_rbr_thr_dll().divisor_latch_low_byte();
+        let dlh = uart.read_ier_dlh().divisor_latch_high_byte();
+        u16::from_le_bytes([dll, dlh])
+    })
 }
 
 /// Sets the divisor value in UART registers.
-pub(crate) fn set_divisor(uart: &RegisterBlock, divisor: u16) {
-    let lcr = uart.lcr.read();
+pub(crate) fn set_divisor(uart: &impl UartRegisters, divisor: u16) {
     let [divisor_low, divisor_high] = divisor.to_le_bytes();
-    unsafe {
-        uart.lcr.write(lcr.enable_divisor_latch_access());
-    }
-    let dll = uart
-        .rbr_thr_dll
-        .read()
-        .set_divisor_latch_low_byte(divisor_low);
-    let dlh = uart
-        .ier_dlh
-        .read()
-        .set_divisor_latch_high_byte(divisor_high);
-    unsafe {
-        uart.rbr_thr_dll.write(dll);
-        uart.ier_dlh.write(dlh);
-        uart.lcr.write(lcr);
-    }
+    with_dlab(uart, |uart| {
+        let dll = uart
+            .read_rbr_thr_dll()
+            .set_divisor_latch_low_byte(divisor_low);
+        let dlh = uart
+            .read_ier_dlh()
+            .set_divisor_latch_high_byte(divisor_high);
+        unsafe {
+            uart.write_rbr_thr_dll(dll);
+            uart.write_ier_dlh(dlh);
+        }
+    });
 }
 
 /// Gets the current parity mode from UART registers.
-pub(crate) fn parity_mode(uart: &RegisterBlock) -> ParityMode {
-    let lcr = uart.lcr.read();
+pub(crate) fn parity_mode(uart: &impl UartRegisters) -> ParityMode {
+    let lcr = uart.read_lcr();
     let flags = (
         lcr.is_parity_enabled(),
         lcr.parity(),
@@ -140,9 +694,17 @@ pub(crate) fn parity_mode(uart: &RegisterBlock) -> ParityMode {
 }
 
 /// Sets the parity mode in UART registers.
-pub(crate) fn set_parity_mode(uart: &RegisterBlock, parity: ParityMode) {
-    let lcr = uart.lcr.read();
-    let lcr = match parity {
+pub(crate) fn set_parity_mode(uart: &impl UartRegisters, parity: ParityMode) {
+    let lcr = with_parity_mode(uart.read_lcr(), parity);
+    unsafe {
+        uart.write_lcr(lcr);
+    }
+    write_volatile_barrier();
+}
+
+/// Sets the parity bits of a Line Control Register value.
+const fn with_parity_mode(lcr: Lcr, parity: ParityMode) -> Lcr {
+    match parity {
         ParityMode::None => lcr.disable_parity(),
         ParityMode::Odd => lcr
             .enable_parity()
@@ -160,44 +722,344 @@ pub(crate) fn set_parity_mode(uart: &RegisterBlock, parity: ParityMode) {
             .enable_parity()
             .enable_stick_parity()
             .set_parity(Parity::Even),
-    };
-    unsafe {
-        uart.lcr.write(lcr);
     }
 }
 
 /// Gets the current stop bits setting from UART registers.
-pub(crate) fn stop_bits(uart: &RegisterBlock) -> StopBits {
-    uart.lcr.read().stop_bits()
+pub(crate) fn stop_bits(uart: &impl UartRegisters) -> StopBits {
+    uart.read_lcr().stop_bits()
 }
 
 /// Sets the stop bits in UART registers.
-pub(crate) fn set_stop_bits(uart: &RegisterBlock, stop_bits: StopBits) {
-    let lcr = uart.lcr.read().set_stop_bits(stop_bits);
+pub(crate) fn set_stop_bits(uart: &impl UartRegisters, stop_bits: StopBits) {
+    let lcr = uart.read_lcr().set_stop_bits(stop_bits);
     unsafe {
-        uart.lcr.write(lcr);
+        uart.write_lcr(lcr);
     }
+    write_volatile_barrier();
 }
 
 /// Gets the current word length from UART registers.
-pub(crate) fn word_length(uart: &RegisterBlock) -> WordLength {
-    uart.lcr.read().word_length()
+pub(crate) fn word_length(uart: &impl UartRegisters) -> WordLength {
+    uart.read_lcr().word_length()
 }
 
 /// Sets the word length in UART registers.
-pub(crate) fn set_word_length(uart: &RegisterBlock, word_length: WordLength) {
-    let lcr = uart.lcr.read().set_word_length(word_length);
+pub(crate) fn set_word_length(uart: &impl UartRegisters, word_length: WordLength) {
+    let lcr = uart.read_lcr().set_word_length(word_length);
+    unsafe {
+        uart.write_lcr(lcr);
+    }
+    write_volatile_barrier();
+}
+
+/// Gets the current configuration from UART registers.
+pub(crate) fn config(uart: &impl UartRegisters) -> Config {
+    Config {
+        divisor: Some(divisor(uart)),
+        parity_mode: parity_mode(uart),
+        stop_bits: stop_bits(uart),
+        word_length: word_length(uart),
+    }
+}
+
+/// Applies the configuration to UART registers.
+pub(crate) fn set_config(uart: &impl UartRegisters, config: &Config) {
+    if let Some(divisor) = config.divisor {
+        set_divisor(uart, divisor);
+    }
+    write_lcr_from_config(uart, config);
+}
+
+/// Applies the parity, stop bits and word length of the configuration with a single LCR write.
+///
+/// Unlike calling the individual setters in turn, the line never sees a mix of old and new settings.
+/// DLAB and the break control bit are preserved.
+pub(crate) fn write_lcr_from_config(uart: &impl UartRegisters, config: &Config) {
+    let lcr = with_parity_mode(uart.read_lcr(), config.parity_mode)
+        .set_stop_bits(config.stop_bits)
+        .set_word_length(config.word_length);
+    unsafe {
+        uart.write_lcr(lcr);
+    }
+    write_volatile_barrier();
+}
+
+/// Orders a configuration write before any following register access.
+///
+/// Volatile accesses are never reordered by the compiler, but on weakly-ordered
+/// architectures the write buffer or the bus may still let a later data write
+/// reach the device before an earlier configuration write has taken effect.
+/// This issues a target-appropriate device barrier after such writes.
+#[inline]
+pub(crate) fn write_volatile_barrier() {
+    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        core::arch::asm!("dsb sy", options(nostack, preserves_flags));
+    }
+    #[cfg(all(target_arch = "arm", target_feature = "v7"))]
+    unsafe {
+        core::arch::asm!("dsb sy", options(nostack, preserves_flags));
+    }
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
     unsafe {
-        uart.lcr.write(lcr);
+        core::arch::asm!("fence ow, ow", options(nostack, preserves_flags));
     }
 }
 
 /// Checks if the UART is ready to read data.
-pub(crate) fn read_ready(uart: &RegisterBlock) -> bool {
-    uart.lsr.read().is_data_ready()
+pub(crate) fn read_ready(uart: &impl UartRegisters) -> bool {
+    uart.read_lsr().is_data_ready()
 }
 
-/// Checks if the UART is ready to write data.
-pub(crate) fn write_ready(uart: &RegisterBlock) -> bool {
-    uart.lsr.read().is_transmitter_fifo_empty()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn struct_config_default() {
+        assert_eq!(Config::default(), Config::new());
+        assert_eq!(Config::default().divisor, None);
+        assert_eq!(ExtendedConfig::default().config, Config::new());
+    }
+    #[test]
+    fn struct_config_frame_time() {
+        // Test 8N1 at 115200 baud from a 1.8432 MHz clock
+        let config = Config::new().set_divisor(1);
+        assert_eq!(config.frame_bits(), 10);
+        assert_eq!(config.frame_duration_ns(1_843_200), Some(86_805));
+
+        // Test 1.5 stop bits with 5-bit words and parity
+        let config = config
+            .set_word_length(WordLength::Bits5)
+            .set_stop_bits(StopBits::Bits2OrBits1_5)
+            .set_parity_mode(ParityMode::Even);
+        assert_eq!(config.frame_bits(), 9);
+        assert_eq!(config.frame_duration_ns(1_843_200), Some(73_784));
+
+        // Test an unknown baud rate
+        assert_eq!(Config::new().frame_duration_ns(1_843_200), None);
+    }
+
+    #[test]
+    fn struct_config_validate() {
+        assert_eq!(Config::new().validate(), Ok(()));
+
+        // Test 2 stop bits are reported as 1.5 with 5-bit words
+        let config = Config::new()
+            .set_stop_bits(StopBits::Bits2OrBits1_5)
+            .set_word_length(WordLength::Bits5);
+        assert_eq!(config.effective_stop_bits(), EffectiveStopBits::OneAndHalf);
+        assert_eq!(config.validate(), Err(ConfigError::OneAndHalfStopBits));
+
+        let config = config.set_word_length(WordLength::Bits6);
+        assert_eq!(config.effective_stop_bits(), EffectiveStopBits::Two);
+        assert_eq!(config.validate(), Ok(()));
+
+        // Test a zero divisor is rejected first, while an unset one is fine
+        assert_eq!(
+            Config::new().set_divisor(0).validate(),
+            Err(ConfigError::ZeroDivisor)
+        );
+        let config = Config::new()
+            .set_divisor(0)
+            .set_stop_bits(StopBits::Bits2OrBits1_5)
+            .set_word_length(WordLength::Bits5);
+        assert_eq!(config.validate(), Err(ConfigError::ZeroDivisor));
+        let config = config.set_divisor(1);
+        assert_eq!(config.validate(), Err(ConfigError::OneAndHalfStopBits));
+
+        // Test every parity mode is valid with every word length
+        for parity in [
+            ParityMode::None,
+            ParityMode::Odd,
+            ParityMode::Even,
+            ParityMode::Mark,
+            ParityMode::Space,
+        ] {
+            for word_length in [WordLength::Bits5, WordLength::Bits8] {
+                let config = Config::new()
+                    .set_parity_mode(parity)
+                    .set_word_length(word_length);
+                assert_eq!(config.validate(), Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    fn struct_config_init_sequence() {
+        // Test the divisor is written behind DLAB, then the line settings clear it
+        let config = Config::new()
+            .set_divisor(0x1234)
+            .set_parity_mode(ParityMode::Even);
+        assert_eq!(
+            &*config.init_sequence(),
+            &[
+                (RegOffset::Lcr, 0x9B),
+                (RegOffset::RbrThrDll, 0x34),
+                (RegOffset::IerDlh, 0x12),
+                (RegOffset::Lcr, 0x1B),
+            ]
+        );
+
+        // Test only the line settings are written without a divisor
+        assert_eq!(&*Config::new().init_sequence(), &[(RegOffset::Lcr, 0x03)]);
+    }
+
+    #[test]
+    fn with_dlab_restores_lcr() {
+        extern crate std;
+        use crate::mock::MockUart;
+        let mock = MockUart::new();
+        let lcr = Lcr::default()
+            .set_word_length(WordLength::Bits7)
+            .enable_break_control();
+        unsafe { mock.regs.lcr.write(lcr) };
+
+        // Test DLAB is set inside the closure and the LCR restored afterwards
+        let dlab = with_dlab(&mock, |uart| {
+            uart.read_lcr().is_divisor_latch_access_enabled()
+        });
+        assert!(dlab);
+        assert_eq!(mock.regs.lcr.read(), lcr);
+
+        // Test the LCR is restored when the closure panics
+        let result = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+            with_dlab(&mock, |_| panic!("closure panicked"))
+        }));
+        assert!(result.is_err());
+        assert_eq!(mock.regs.lcr.read(), lcr);
+
+        // Test clearing DLAB restores it afterwards
+        let dlab = lcr.enable_divisor_latch_access();
+        unsafe { mock.regs.lcr.write(dlab) };
+        let cleared = without_dlab(&mock, |uart| {
+            !uart.read_lcr().is_divisor_latch_access_enabled()
+        });
+        assert!(cleared);
+        assert_eq!(mock.regs.lcr.read(), dlab);
+    }
+
+    #[test]
+    fn baud_divisor_conversion() {
+        // Test the classic 1.8432 MHz PC clock
+        assert_eq!(baud_to_divisor(1_843_200, 115_200), 1);
+        assert_eq!(baud_to_divisor(1_843_200, 9_600), 12);
+        assert_eq!(divisor_to_baud(1_843_200, 12), 9_600);
+
+        // Test rounding to the nearest divisor
+        assert_eq!(baud_to_divisor(24_000_000, 115_200), 13);
+        assert_eq!(divisor_to_baud(24_000_000, 13), 115_384);
+
+        // Test out of range inputs are clamped
+        assert_eq!(baud_to_divisor(1_843_200, 0), 0);
+        assert_eq!(baud_to_divisor(1_000, 115_200), 1);
+        assert_eq!(baud_to_divisor(u32::MAX, 1), u16::MAX);
+        assert_eq!(baud_to_divisor(u32::MAX, u32::MAX), 1);
+        assert_eq!(divisor_to_baud(1_843_200, 0), 0);
+
+        // Test the bit time at 115200 and 9600 baud, and an unknown clock
+        assert_eq!(bit_duration_ns(1_843_200, 1), 8_680);
+        assert_eq!(bit_duration_ns(1_843_200, 12), 104_166);
+        assert_eq!(bit_duration_ns(0, 12), 0);
+
+        // Test the typed configuration setter
+        let config = Config::new().set_baud_rate(Hertz(1_843_200), Baud(9_600));
+        assert_eq!(config.divisor, Some(12));
+        let config = Config::new().set_baud_rate(1_843_200.into(), 9_600.into());
+        assert_eq!(config.divisor, Some(12));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn baud_divisor_conversion_never_panics(clock: u32, baud: u32, divisor: u16) {
+            let result = baud_to_divisor(clock, baud);
+            proptest::prop_assert!(baud == 0 || result >= 1);
+            divisor_to_baud(clock, result);
+            divisor_to_baud(clock, divisor);
+        }
+
+        #[test]
+        fn baud_divisor_round_trip(
+            clock in proptest::prop_oneof![
+                1_000_000_u32..=100_000_000,
+                (u32::MAX - 1_000_000)..=u32::MAX,
+            ],
+            baud in 50_u32..=4_000_000,
+        ) {
+            // Only bauds whose ideal divisor is in range can be reached
+            let sample_rate = 16 * baud as u64;
+            proptest::prop_assume!(
+                sample_rate <= clock as u64 && clock as u64 <= sample_rate * u16::MAX as u64
+            );
+            let divisor = baud_to_divisor(clock, baud);
+
+            // Test the divisor is the nearest one, i.e. off by at most half a step
+            let error = (clock as u64).abs_diff(sample_rate * divisor as u64);
+            proptest::prop_assert!(error <= sample_rate / 2);
+
+            // Test the resulting baud is off by at most 1 / (2 * divisor), plus 1 from truncation
+            let actual = divisor_to_baud(clock, divisor) as u64;
+            let bound = baud as u64 + 2 * divisor as u64;
+            proptest::prop_assert!(actual.abs_diff(baud as u64) * 2 * divisor as u64 <= bound);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn struct_config_serde_round_trip() {
+        use serde_test::{Token, assert_tokens};
+        let config = Config::new()
+            .set_divisor(12)
+            .set_parity_mode(ParityMode::Even)
+            .set_stop_bits(StopBits::Bits2OrBits1_5)
+            .set_word_length(WordLength::Bits7);
+        assert_tokens(
+            &config,
+            &[
+                Token::Struct {
+                    name: "Config",
+                    len: 4,
+                },
+                Token::Str("divisor"),
+                Token::Some,
+                Token::U16(12),
+                Token::Str("parity_mode"),
+                Token::UnitVariant {
+                    name: "ParityMode",
+                    variant: "Even",
+                },
+                Token::Str("stop_bits"),
+                Token::UnitVariant {
+                    name: "StopBits",
+                    variant: "Bits2OrBits1_5",
+                },
+                Token::Str("word_length"),
+                Token::UnitVariant {
+                    name: "WordLength",
+                    variant: "Bits7",
+                },
+                Token::StructEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn enum_parity_mode_try_from_u8() {
+        assert_eq!(ParityMode::try_from(0b000), Ok(ParityMode::None));
+        assert_eq!(ParityMode::try_from(0b001), Ok(ParityMode::Odd));
+        assert_eq!(ParityMode::try_from(0b011), Ok(ParityMode::Even));
+        assert_eq!(ParityMode::try_from(0b101), Ok(ParityMode::High));
+        assert_eq!(ParityMode::try_from(0b111), Ok(ParityMode::Low));
+        assert_eq!(ParityMode::try_from(0b101), Ok(ParityMode::Mark));
+        assert_eq!(ParityMode::try_from(0b111), Ok(ParityMode::Space));
+
+        // Test reserved combinations and out of range values
+        for val in [0b010, 0b100, 0b110, 0x08, 0xFF] {
+            assert_eq!(
+                ParityMode::try_from(val),
+                Err(DecodeError::InvalidParityMode(val))
+            );
+        }
+    }
 }
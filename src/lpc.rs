@@ -0,0 +1,157 @@
+#![allow(unused)]
+#![allow(clippy::identity_op)]
+
+use crate::divisor;
+use crate::register::RegisterBlock;
+use core::ops::Deref;
+use volatile_register::RW;
+
+/// NXP LPC UART Register Block.
+///
+/// The LPC family UARTs are 16550-compatible with additional vendor registers,
+/// including a hardware auto-baud unit.
+/// The first eight registers follow the standard 16550 layout,
+/// and this structure dereferences to the standard [`RegisterBlock`] for them.
+#[repr(C)]
+pub struct LpcRegisterBlock {
+    /// Standard 16550 registers.
+    pub uart: RegisterBlock,
+    /// Auto-baud Control Register.
+    pub acr: RW<Acr>,
+}
+
+impl Deref for LpcRegisterBlock {
+    type Target = RegisterBlock;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.uart
+    }
+}
+
+impl LpcRegisterBlock {
+    /// Starts hardware auto-baud detection.
+    ///
+    /// The auto-baud unit measures the start bit of the next incoming character,
+    /// and the start bit plus the least significant bit in [`AutobaudMode::Mode0`].
+    /// The host should send an `'A'` or `'a'`, whose least significant bit is 1.
+    /// On completion the detected divisor is written to DLL and DLM.
+    pub fn start_autobaud(&self, mode: AutobaudMode) {
+        let acr = Acr::default()
+            .set_mode(mode)
+            .clear_autobaud_interrupts()
+            .start();
+        unsafe {
+            self.acr.write(acr);
+        }
+    }
+
+    /// Checks whether auto-baud detection has completed.
+    ///
+    /// Returns the detected divisor once the auto-baud unit has finished, or `None` while it is still running.
+    pub fn poll_autobaud(&self) -> Option<u16> {
+        if self.acr.read().is_started() {
+            return None;
+        }
+        Some(divisor(&self.uart))
+    }
+}
+
+/// Represents the auto-baud measurement modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutobaudMode {
+    /// Measure the start bit and the least significant data bit.
+    Mode0 = 0x00,
+    /// Measure the start bit only.
+    Mode1 = 0x01,
+}
+
+/// Auto-baud Control Register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct Acr(u32);
+
+impl Acr {
+    /// Auto-baud start.
+    const START: u32 = 0x01 << 0;
+    /// Auto-baud mode select.
+    const MODE: u32 = 0x01 << 1;
+    /// Restart on timeout.
+    const AUTORESTART: u32 = 0x01 << 2;
+    /// End of auto-baud interrupt clear.
+    const ABEOINTCLR: u32 = 0x01 << 8;
+    /// Auto-baud timeout interrupt clear.
+    const ABTOINTCLR: u32 = 0x01 << 9;
+
+    /// Starts the auto-baud unit.
+    /// This function sets the start bit, which the hardware clears on completion.
+    #[inline]
+    pub const fn start(self) -> Self {
+        Self(self.0 | Self::START)
+    }
+
+    /// Checks if auto-baud detection is running.
+    /// Returns true if the start bit is still set.
+    #[inline]
+    pub const fn is_started(self) -> bool {
+        (self.0 & Self::START) != 0
+    }
+
+    /// Sets the auto-baud mode.
+    #[inline]
+    pub const fn set_mode(self, val: AutobaudMode) -> Self {
+        Self((self.0 & !Self::MODE) | ((val as u32) << 1))
+    }
+
+    /// Sets whether auto-baud restarts after a timeout.
+    #[inline]
+    pub const fn set_auto_restart(self, val: bool) -> Self {
+        if val {
+            Self(self.0 | Self::AUTORESTART)
+        } else {
+            Self(self.0 & !Self::AUTORESTART)
+        }
+    }
+
+    /// Clears the end of auto-baud and auto-baud timeout interrupts.
+    #[inline]
+    pub const fn clear_autobaud_interrupts(self) -> Self {
+        Self(self.0 | Self::ABEOINTCLR | Self::ABTOINTCLR)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::mem::offset_of;
+    #[test]
+    fn struct_lpc_register_block_offset() {
+        assert_eq!(offset_of!(LpcRegisterBlock, uart), 0x00);
+        assert_eq!(offset_of!(LpcRegisterBlock, acr), 0x20);
+    }
+    #[test]
+    fn struct_acr_functions() {
+        let mut val = Acr(0x0);
+
+        // Test starting auto-baud
+        val = val.start();
+        assert_eq!(val.0, 0x00000001);
+        assert!(val.is_started());
+
+        // Test mode selection
+        val = Acr(0x0).set_mode(AutobaudMode::Mode1);
+        assert_eq!(val.0, 0x00000002);
+        val = val.set_mode(AutobaudMode::Mode0);
+        assert_eq!(val.0, 0x00000000);
+
+        // Test auto restart
+        val = Acr(0x0).set_auto_restart(true);
+        assert_eq!(val.0, 0x00000004);
+        val = val.set_auto_restart(false);
+        assert_eq!(val.0, 0x00000000);
+
+        // Test clearing the interrupts
+        val = Acr(0x0).clear_autobaud_interrupts();
+        assert_eq!(val.0, 0x00000300);
+    }
+}
@@ -0,0 +1,191 @@
+//! Memory-backed register blocks for host tests.
+
+use crate::register::{
+    FifoStatus, IerDlh, IirFcr, Lcr, Lsr, Mcr, Msr, RbrThrDll, RegisterBlock, Scr, UartRegisters,
+};
+use core::cell::Cell;
+use volatile_register::RW;
+
+/// Creates a register block in memory with an idle transmitter.
+pub(crate) fn mock_uart() -> RegisterBlock {
+    let uart: RegisterBlock = unsafe { core::mem::zeroed() };
+    set_lsr(&uart, 0x60);
+    uart
+}
+
+/// Sets the raw value of the read-only Line Status Register.
+pub(crate) fn set_lsr(uart: &RegisterBlock, val: u32) {
+    unsafe { (&uart.lsr as *const _ as *mut u32).write_volatile(val) }
+}
+
+/// A register model with the divisor latch aliased behind DLAB, as on the hardware.
+///
+/// Unlike a plain memory-backed [`RegisterBlock`], writing the IER does not change the divisor,
+/// and the FCR is kept apart from the IIR: the FIFO status bits of the IIR follow the FIFO enable bit
+/// of the last FCR write and the FIFOs the modelled part has, a 16550A by default.
+/// It also counts LCR and divisor latch writes, and can model a receiver holding a number of bytes.
+pub(crate) struct MockUart {
+    pub(crate) regs: RegisterBlock,
+    dll: RW<RbrThrDll>,
+    dlh: RW<IerDlh>,
+    pub(crate) lcr_writes: Cell<usize>,
+    pub(crate) divisor_writes: Cell<usize>,
+    /// Bytes waiting in the receiver; when set, data ready follows it instead of the raw LSR.
+    rx_pending: Cell<Option<usize>>,
+    /// The last value written to the FCR, with the 64-byte bit only taken while DLAB is set.
+    fcr: Cell<IirFcr>,
+    /// The FIFO status the IIR reports while FIFOs are enabled; `Disabled` models a 16450.
+    fifo_status: Cell<FifoStatus>,
+    /// Whether the part has the 64-byte FIFO mode of a 16750.
+    fifo64: Cell<bool>,
+}
+
+impl MockUart {
+    /// Creates a register model of a 16550A with an idle transmitter and no interrupt pending.
+    pub(crate) fn new() -> Self {
+        let regs = mock_uart();
+        unsafe { regs.iir_fcr.write(IirFcr::from_bits(0x01)) };
+        MockUart {
+            regs,
+            dll: unsafe { core::mem::zeroed() },
+            dlh: unsafe { core::mem::zeroed() },
+            lcr_writes: Cell::new(0),
+            divisor_writes: Cell::new(0),
+            rx_pending: Cell::new(None),
+            fcr: Cell::new(IirFcr::default()),
+            fifo_status: Cell::new(FifoStatus::Enabled),
+            fifo64: Cell::new(false),
+        }
+    }
+
+    /// Sets the FIFO status the IIR reports while FIFOs are enabled, modelling the FIFOs of the part.
+    pub(crate) fn set_fifo_status(&self, status: FifoStatus) {
+        self.fifo_status.set(status);
+    }
+
+    /// Gives the part the 64-byte FIFO mode of a 16750.
+    pub(crate) fn set_fifo64(&self, fifo64: bool) {
+        self.fifo64.set(fifo64);
+    }
+
+    /// Gets the last value written to the FCR.
+    pub(crate) fn fcr(&self) -> IirFcr {
+        self.fcr.get()
+    }
+
+    /// Makes the receiver hold `count` bytes, each read of the receiver buffer taking one.
+    pub(crate) fn set_rx_pending(&self, count: usize) {
+        self.rx_pending.set(Some(count));
+    }
+
+    fn dlab(&self) -> bool {
+        self.regs.lcr.read().is_divisor_latch_access_enabled()
+    }
+}
+
+impl UartRegisters for MockUart {
+    fn read_rbr_thr_dll(&self) -> RbrThrDll {
+        match self.dlab() {
+            true => self.dll.read(),
+            false => {
+                if let Some(count) = self.rx_pending.get() {
+                    self.rx_pending.set(Some(count.saturating_sub(1)));
+                }
+                self.regs.rbr_thr_dll.read()
+            }
+        }
+    }
+
+    unsafe fn write_rbr_thr_dll(&self, val: RbrThrDll) {
+        match self.dlab() {
+            true => unsafe {
+                self.divisor_writes.set(self.divisor_writes.get() + 1);
+                self.dll.write(val)
+            },
+            false => unsafe { self.regs.rbr_thr_dll.write(val) },
+        }
+    }
+
+    fn read_ier_dlh(&self) -> IerDlh {
+        match self.dlab() {
+            true => self.dlh.read(),
+            false => self.regs.ier_dlh.read(),
+        }
+    }
+
+    unsafe fn write_ier_dlh(&self, val: IerDlh) {
+        match self.dlab() {
+            true => unsafe {
+                self.divisor_writes.set(self.divisor_writes.get() + 1);
+                self.dlh.write(val)
+            },
+            false => unsafe { self.regs.ier_dlh.write(val) },
+        }
+    }
+
+    fn read_iir_fcr(&self) -> IirFcr {
+        // The interrupt identification comes from the raw register, the FIFO bits from the model.
+        let fcr = self.fcr.get();
+        let status = match fcr.is_fifo_enable_set() {
+            true => self.fifo_status.get(),
+            false => FifoStatus::Disabled,
+        };
+        let mut bits = self.regs.iir_fcr.read().bits() & 0x0F;
+        bits |= match status {
+            FifoStatus::Disabled => 0x00,
+            FifoStatus::EnabledNonFunctioning => 0x80,
+            FifoStatus::Enabled => 0xC0,
+        };
+        if status == FifoStatus::Enabled && self.fifo64.get() && fcr.is_64byte_fifo_enabled() {
+            bits |= 0x20;
+        }
+        IirFcr::from_bits(bits)
+    }
+
+    unsafe fn write_iir_fcr(&self, val: IirFcr) {
+        // As on a 16750, the 64-byte mode can only be changed while DLAB is set.
+        let val = match (self.dlab(), self.fcr.get().is_64byte_fifo_enabled()) {
+            (true, _) => val,
+            (false, true) => val.enable_64byte_fifo(),
+            (false, false) => val.disable_64byte_fifo(),
+        };
+        self.fcr.set(val);
+    }
+
+    fn read_lcr(&self) -> Lcr {
+        self.regs.read_lcr()
+    }
+
+    unsafe fn write_lcr(&self, val: Lcr) {
+        self.lcr_writes.set(self.lcr_writes.get() + 1);
+        unsafe { self.regs.write_lcr(val) }
+    }
+
+    fn read_mcr(&self) -> Mcr {
+        self.regs.read_mcr()
+    }
+
+    unsafe fn write_mcr(&self, val: Mcr) {
+        unsafe { self.regs.write_mcr(val) }
+    }
+
+    fn read_lsr(&self) -> Lsr {
+        if let Some(count) = self.rx_pending.get() {
+            let raw = unsafe { (&self.regs.lsr as *const _ as *const u32).read_volatile() };
+            set_lsr(&self.regs, (raw & !0x01) | (count > 0) as u32);
+        }
+        self.regs.read_lsr()
+    }
+
+    fn read_msr(&self) -> Msr {
+        self.regs.read_msr()
+    }
+
+    fn read_scr(&self) -> Scr {
+        self.regs.read_scr()
+    }
+
+    unsafe fn write_scr(&self, val: Scr) {
+        unsafe { self.regs.write_scr(val) }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::register::RegisterBlock;
+use crate::{DwRegisterBlock, LpcRegisterBlock, UartError};
+
+/// Control of the TX and RX signal polarity, on parts that can invert them.
+///
+/// Some SoC UARTs can invert their serial signals, which saves an external inverter
+/// in front of an optocoupler or an inverting transceiver. The standard 16550 cannot,
+/// so this is part-specific: each register block reports what its silicon supports,
+/// and an inversion request on a part without it returns [`UartError::Unsupported`].
+/// Requesting normal polarity always succeeds, as that is what such parts do.
+///
+/// Neither the DesignWare APB UART nor the LPC UART has polarity control,
+/// so [`DwRegisterBlock`] and [`LpcRegisterBlock`] use the default methods.
+pub trait PolarityControl {
+    /// Checks if the transmit signal can be inverted.
+    fn supports_tx_invert(&self) -> bool {
+        false
+    }
+
+    /// Checks if the receive signal can be inverted.
+    fn supports_rx_invert(&self) -> bool {
+        false
+    }
+
+    /// Inverts the transmit signal, or restores its normal polarity.
+    fn set_tx_invert(&self, invert: bool) -> Result<(), UartError> {
+        match invert {
+            true => Err(UartError::Unsupported),
+            false => Ok(()),
+        }
+    }
+
+    /// Inverts the receive signal, or restores its normal polarity.
+    fn set_rx_invert(&self, invert: bool) -> Result<(), UartError> {
+        match invert {
+            true => Err(UartError::Unsupported),
+            false => Ok(()),
+        }
+    }
+}
+
+impl PolarityControl for RegisterBlock {}
+
+impl PolarityControl for DwRegisterBlock {}
+
+impl PolarityControl for LpcRegisterBlock {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::mock_uart;
+
+    #[test]
+    fn polarity_control_unsupported() {
+        let uart = mock_uart();
+        assert!(!uart.supports_tx_invert());
+        assert!(!uart.supports_rx_invert());
+
+        // Test only normal polarity is accepted
+        assert_eq!(uart.set_tx_invert(true), Err(UartError::Unsupported));
+        assert_eq!(uart.set_rx_invert(true), Err(UartError::Unsupported));
+        assert_eq!(uart.set_tx_invert(false), Ok(()));
+        assert_eq!(uart.set_rx_invert(false), Ok(()));
+    }
+}
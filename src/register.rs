@@ -1,4 +1,5 @@
 #![allow(unused)]
+#![allow(clippy::identity_op)]
 
 use volatile_register::{RO, RW};
 
@@ -26,6 +27,492 @@ pub struct RegisterBlock {
     pub scr: RW<Scr>,
 }
 
+/// Declares a handle to a UART whose [`RegisterBlock`] sits at a fixed address or linker symbol.
+///
+/// `static_uart!(UART0 => 0x1000_0000)` declares a unit struct `UART0` with an associated
+/// `registers()` function returning the `&'static RegisterBlock`, and dereferencing to it, so the handle
+/// itself can be passed to [`BlockingUart::new`](crate::BlockingUart::new).
+/// For an address, a misaligned or null value is rejected at compile time, and it is kept as `UART0::ADDRESS`.
+/// `static_uart!(UART0 => extern __uart0)` takes the address of the linker symbol `__uart0` instead,
+/// which the linker script must place at the UART, aligned to 4 bytes.
+/// Attributes and a visibility may precede the name.
+///
+/// The unsafe pointer cast is done once here. Invoking the macro asserts that the address or symbol
+/// is a 16550-compatible UART with a 4-byte register stride, mapped for the whole program;
+/// handles are freely copied, so each one may reach the same registers.
+///
+/// # Example
+///
+/// ```
+/// use uart16550::{BlockingUart, Config, static_uart};
+///
+/// static_uart!(
+///     /// The console UART.
+///     pub UART0 => 0x1000_0000
+/// );
+///
+/// fn console() -> BlockingUart<UART0> {
+///     BlockingUart::new(UART0, Config::new(), true)
+/// }
+/// ```
+#[macro_export]
+macro_rules! static_uart {
+    ($(#[$attr:meta])* $vis:vis $name:ident => extern $symbol:ident) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug)]
+        $vis struct $name;
+
+        impl $name {
+            /// Gets the registers of this UART.
+            #[inline]
+            pub fn registers() -> &'static $crate::RegisterBlock {
+                unsafe extern "C" {
+                    static $symbol: $crate::RegisterBlock;
+                }
+                unsafe { &$symbol }
+            }
+        }
+
+        $crate::static_uart!(@deref $name);
+    };
+    ($(#[$attr:meta])* $vis:vis $name:ident => $address:expr) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug)]
+        $vis struct $name;
+
+        impl $name {
+            /// The base address of this UART.
+            pub const ADDRESS: usize = $address;
+
+            /// Gets the registers of this UART.
+            #[inline]
+            pub fn registers() -> &'static $crate::RegisterBlock {
+                unsafe { &*(Self::ADDRESS as *const $crate::RegisterBlock) }
+            }
+        }
+
+        const _: () = ::core::assert!(
+            $name::ADDRESS != 0
+                && $name::ADDRESS % ::core::mem::align_of::<$crate::RegisterBlock>() == 0,
+            "UART address must be non-null and aligned to 4 bytes"
+        );
+
+        $crate::static_uart!(@deref $name);
+    };
+    (@deref $name:ident) => {
+        impl ::core::ops::Deref for $name {
+            type Target = $crate::RegisterBlock;
+
+            #[inline]
+            fn deref(&self) -> &Self::Target {
+                Self::registers()
+            }
+        }
+    };
+}
+
+/// Index of a register in the standard 16550 layout.
+///
+/// Registers sharing an address have a single index, and which one is reached depends on DLAB.
+/// The byte offset is the index times the register stride, which is 4 for [`RegisterBlock`]
+/// and 1 on parts with byte-spaced registers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegOffset {
+    /// Receive Buffer Register / Transmit Holding Register / Divisor Latch LSB.
+    RbrThrDll = 0,
+    /// Interrupt Enable Register / Divisor Latch MSB.
+    IerDlh = 1,
+    /// Interrupt Identification Register / FIFO Control Register.
+    IirFcr = 2,
+    /// Line Control Register.
+    Lcr = 3,
+    /// Modem Control Register.
+    Mcr = 4,
+    /// Line Status Register.
+    Lsr = 5,
+    /// Modem Status Register.
+    Msr = 6,
+    /// Scratchpad Register.
+    Scr = 7,
+}
+
+impl RegOffset {
+    /// Gets the byte offset of the register, given the distance in bytes between two registers.
+    #[inline]
+    pub const fn byte_offset(self, stride: usize) -> usize {
+        self as usize * stride
+    }
+}
+
+/// Access to the registers of a 16550-compatible UART.
+///
+/// [`BlockingUart`](crate::BlockingUart) and the types built on it use this trait for every register access,
+/// so a UART can be reached through any backend: memory-mapped registers with a byte or 32-bit stride,
+/// an I/O port accessor, or a software model for tests.
+/// It is implemented for [`RegisterBlock`], and for any pointer type that dereferences to an implementation,
+/// such as `&'static RegisterBlock` or `&DwRegisterBlock`.
+///
+/// Each method accesses one register exactly once. Registers sharing an address are not told apart:
+/// which one is reached depends on DLAB, as on the hardware.
+///
+/// # Safety
+///
+/// Writing a register reconfigures the hardware, which may break invariants that other code relies on,
+/// e.g. a divisor latch left enabled or a DMA engine fed by the FIFOs.
+/// Callers of the write methods must make sure the write is sound for the system.
+pub trait UartRegisters {
+    /// Reads the Receive Buffer Register, or Divisor Latch LSB while DLAB is set.
+    fn read_rbr_thr_dll(&self) -> RbrThrDll;
+
+    /// Writes the Transmit Holding Register, or Divisor Latch LSB while DLAB is set.
+    ///
+    /// # Safety
+    ///
+    /// See the [trait documentation](UartRegisters#safety).
+    unsafe fn write_rbr_thr_dll(&self, val: RbrThrDll);
+
+    /// Reads the Interrupt Enable Register, or Divisor Latch MSB while DLAB is set.
+    fn read_ier_dlh(&self) -> IerDlh;
+
+    /// Writes the Interrupt Enable Register, or Divisor Latch MSB while DLAB is set.
+    ///
+    /// # Safety
+    ///
+    /// See the [trait documentation](UartRegisters#safety).
+    unsafe fn write_ier_dlh(&self, val: IerDlh);
+
+    /// Reads the Interrupt Identification Register.
+    fn read_iir_fcr(&self) -> IirFcr;
+
+    /// Writes the FIFO Control Register.
+    ///
+    /// # Safety
+    ///
+    /// See the [trait documentation](UartRegisters#safety).
+    unsafe fn write_iir_fcr(&self, val: IirFcr);
+
+    /// Reads the Line Control Register.
+    fn read_lcr(&self) -> Lcr;
+
+    /// Writes the Line Control Register.
+    ///
+    /// # Safety
+    ///
+    /// See the [trait documentation](UartRegisters#safety).
+    unsafe fn write_lcr(&self, val: Lcr);
+
+    /// Reads the Modem Control Register.
+    fn read_mcr(&self) -> Mcr;
+
+    /// Writes the Modem Control Register.
+    ///
+    /// # Safety
+    ///
+    /// See the [trait documentation](UartRegisters#safety).
+    unsafe fn write_mcr(&self, val: Mcr);
+
+    /// Reads the Line Status Register.
+    fn read_lsr(&self) -> Lsr;
+
+    /// Reads the Modem Status Register.
+    fn read_msr(&self) -> Msr;
+
+    /// Reads the Scratchpad Register.
+    fn read_scr(&self) -> Scr;
+
+    /// Writes the Scratchpad Register.
+    ///
+    /// # Safety
+    ///
+    /// See the [trait documentation](UartRegisters#safety).
+    unsafe fn write_scr(&self, val: Scr);
+}
+
+impl UartRegisters for RegisterBlock {
+    #[inline]
+    fn read_rbr_thr_dll(&self) -> RbrThrDll {
+        self.rbr_thr_dll.read()
+    }
+
+    #[inline]
+    unsafe fn write_rbr_thr_dll(&self, val: RbrThrDll) {
+        unsafe { self.rbr_thr_dll.write(val) }
+    }
+
+    #[inline]
+    fn read_ier_dlh(&self) -> IerDlh {
+        self.ier_dlh.read()
+    }
+
+    #[inline]
+    unsafe fn write_ier_dlh(&self, val: IerDlh) {
+        unsafe { self.ier_dlh.write(val) }
+    }
+
+    #[inline]
+    fn read_iir_fcr(&self) -> IirFcr {
+        self.iir_fcr.read()
+    }
+
+    #[inline]
+    unsafe fn write_iir_fcr(&self, val: IirFcr) {
+        unsafe { self.iir_fcr.write(val) }
+    }
+
+    #[inline]
+    fn read_lcr(&self) -> Lcr {
+        self.lcr.read()
+    }
+
+    #[inline]
+    unsafe fn write_lcr(&self, val: Lcr) {
+        unsafe { self.lcr.write(val) }
+    }
+
+    #[inline]
+    fn read_mcr(&self) -> Mcr {
+        self.mcr.read()
+    }
+
+    #[inline]
+    unsafe fn write_mcr(&self, val: Mcr) {
+        unsafe { self.mcr.write(val) }
+    }
+
+    #[inline]
+    fn read_lsr(&self) -> Lsr {
+        self.lsr.read()
+    }
+
+    #[inline]
+    fn read_msr(&self) -> Msr {
+        self.msr.read()
+    }
+
+    #[inline]
+    fn read_scr(&self) -> Scr {
+        self.scr.read()
+    }
+
+    #[inline]
+    unsafe fn write_scr(&self, val: Scr) {
+        unsafe { self.scr.write(val) }
+    }
+}
+
+/// Width of each register access, as given by the devicetree `reg-io-width` property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegIoWidth {
+    /// 8-bit accesses.
+    U8 = 1,
+    /// 32-bit accesses, for buses that reject narrower ones.
+    U32 = 4,
+}
+
+/// Memory-mapped registers at a runtime base address, with a configurable stride and access width.
+///
+/// This covers the layouts described by the devicetree `reg-shift` and `reg-io-width` properties,
+/// which [`RegisterBlock`] cannot express, as its 4-byte stride and 32-bit accesses are fixed.
+/// Register `n` is at `base + (n << reg_shift)`; with 32-bit accesses only the low byte is significant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MmioRegisters {
+    base: usize,
+    reg_shift: u8,
+    width: RegIoWidth,
+}
+
+impl MmioRegisters {
+    /// Creates an accessor for the registers at `base`.
+    ///
+    /// # Safety
+    ///
+    /// `base` must be the address of a 16550-compatible UART with the given layout,
+    /// valid for volatile accesses of `width` to all eight registers for as long as the accessor is used.
+    #[inline]
+    pub const unsafe fn new(base: usize, reg_shift: u8, width: RegIoWidth) -> Self {
+        MmioRegisters {
+            base,
+            reg_shift,
+            width,
+        }
+    }
+
+    #[inline]
+    fn addr(&self, reg: RegOffset) -> usize {
+        self.base + reg.byte_offset(1 << self.reg_shift)
+    }
+
+    #[inline]
+    fn read(&self, reg: RegOffset) -> u32 {
+        let addr = self.addr(reg);
+        unsafe {
+            match self.width {
+                RegIoWidth::U8 => (addr as *const u8).read_volatile() as u32,
+                RegIoWidth::U32 => (addr as *const u32).read_volatile(),
+            }
+        }
+    }
+
+    #[inline]
+    unsafe fn write(&self, reg: RegOffset, val: u32) {
+        let addr = self.addr(reg);
+        unsafe {
+            match self.width {
+                RegIoWidth::U8 => (addr as *mut u8).write_volatile(val as u8),
+                RegIoWidth::U32 => (addr as *mut u32).write_volatile(val),
+            }
+        }
+    }
+}
+
+impl UartRegisters for MmioRegisters {
+    #[inline]
+    fn read_rbr_thr_dll(&self) -> RbrThrDll {
+        RbrThrDll(self.read(RegOffset::RbrThrDll))
+    }
+
+    #[inline]
+    unsafe fn write_rbr_thr_dll(&self, val: RbrThrDll) {
+        unsafe { self.write(RegOffset::RbrThrDll, val.0) }
+    }
+
+    #[inline]
+    fn read_ier_dlh(&self) -> IerDlh {
+        IerDlh(self.read(RegOffset::IerDlh))
+    }
+
+    #[inline]
+    unsafe fn write_ier_dlh(&self, val: IerDlh) {
+        unsafe { self.write(RegOffset::IerDlh, val.0) }
+    }
+
+    #[inline]
+    fn read_iir_fcr(&self) -> IirFcr {
+        IirFcr(self.read(RegOffset::IirFcr))
+    }
+
+    #[inline]
+    unsafe fn write_iir_fcr(&self, val: IirFcr) {
+        unsafe { self.write(RegOffset::IirFcr, val.0) }
+    }
+
+    #[inline]
+    fn read_lcr(&self) -> Lcr {
+        Lcr(self.read(RegOffset::Lcr))
+    }
+
+    #[inline]
+    unsafe fn write_lcr(&self, val: Lcr) {
+        unsafe { self.write(RegOffset::Lcr, val.0) }
+    }
+
+    #[inline]
+    fn read_mcr(&self) -> Mcr {
+        Mcr(self.read(RegOffset::Mcr))
+    }
+
+    #[inline]
+    unsafe fn write_mcr(&self, val: Mcr) {
+        unsafe { self.write(RegOffset::Mcr, val.0) }
+    }
+
+    #[inline]
+    fn read_lsr(&self) -> Lsr {
+        Lsr(self.read(RegOffset::Lsr))
+    }
+
+    #[inline]
+    fn read_msr(&self) -> Msr {
+        Msr(self.read(RegOffset::Msr))
+    }
+
+    #[inline]
+    fn read_scr(&self) -> Scr {
+        Scr(self.read(RegOffset::Scr))
+    }
+
+    #[inline]
+    unsafe fn write_scr(&self, val: Scr) {
+        unsafe { self.write(RegOffset::Scr, val.0) }
+    }
+}
+
+impl<T> UartRegisters for T
+where
+    T: core::ops::Deref,
+    T::Target: UartRegisters,
+{
+    #[inline]
+    fn read_rbr_thr_dll(&self) -> RbrThrDll {
+        (**self).read_rbr_thr_dll()
+    }
+
+    #[inline]
+    unsafe fn write_rbr_thr_dll(&self, val: RbrThrDll) {
+        unsafe { (**self).write_rbr_thr_dll(val) }
+    }
+
+    #[inline]
+    fn read_ier_dlh(&self) -> IerDlh {
+        (**self).read_ier_dlh()
+    }
+
+    #[inline]
+    unsafe fn write_ier_dlh(&self, val: IerDlh) {
+        unsafe { (**self).write_ier_dlh(val) }
+    }
+
+    #[inline]
+    fn read_iir_fcr(&self) -> IirFcr {
+        (**self).read_iir_fcr()
+    }
+
+    #[inline]
+    unsafe fn write_iir_fcr(&self, val: IirFcr) {
+        unsafe { (**self).write_iir_fcr(val) }
+    }
+
+    #[inline]
+    fn read_lcr(&self) -> Lcr {
+        (**self).read_lcr()
+    }
+
+    #[inline]
+    unsafe fn write_lcr(&self, val: Lcr) {
+        unsafe { (**self).write_lcr(val) }
+    }
+
+    #[inline]
+    fn read_mcr(&self) -> Mcr {
+        (**self).read_mcr()
+    }
+
+    #[inline]
+    unsafe fn write_mcr(&self, val: Mcr) {
+        unsafe { (**self).write_mcr(val) }
+    }
+
+    #[inline]
+    fn read_lsr(&self) -> Lsr {
+        (**self).read_lsr()
+    }
+
+    #[inline]
+    fn read_msr(&self) -> Msr {
+        (**self).read_msr()
+    }
+
+    #[inline]
+    fn read_scr(&self) -> Scr {
+        (**self).read_scr()
+    }
+
+    #[inline]
+    unsafe fn write_scr(&self, val: Scr) {
+        unsafe { (**self).write_scr(val) }
+    }
+}
+
 /// RbrThrDll represents a register that can be used as Receive Buffer Register (RBR), Transmit Holding Register (THR), or Divisor Latch LSB (DLL).
 /// This register serves multiple purposes depending on the DLAB bit setting in the Line Control Register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
@@ -54,6 +541,13 @@ impl RbrThrDll {
         Self((self.0 & !Self::THR) | ((val as u32) & Self::THR))
     }
 
+    /// Creates a Transmit Holding Register value holding a byte to be transmitted.
+    /// This is the same as setting the data on a zero value, without reading the register first.
+    #[inline]
+    pub const fn from_transmitter_data(val: u8) -> Self {
+        Self(val as u32)
+    }
+
     /// Gets the value from the Divisor Latch Low Byte.
     /// Returns the lower byte of the baud rate divisor.
     #[inline]
@@ -206,8 +700,24 @@ pub enum InterruptId {
     CharacterTimeout = 0x0C,
 }
 
+/// Represents the FIFO status reported in bits 6 and 7 of the IIR.
+///
+/// The FCR is write-only, so this is the only way to learn whether FIFOs are really on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FifoStatus {
+    /// Neither bit is set: FIFOs are disabled, or the part has none, such as a 16450.
+    Disabled,
+    /// Only one bit is set: FIFOs are enabled but not functioning, as on the original 16550.
+    EnabledNonFunctioning,
+    /// Both bits are set: FIFOs are enabled and functioning.
+    Enabled,
+}
+
 /// Represents the DMA operating modes.
 /// Controls how DMA transfers are handled.
+///
+/// The mode selects how the TXRDY and RXRDY pins request transfers from a DMA controller;
+/// it has no effect on parts or boards where those pins are not wired.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DmaMode {
     /// Mode 0 DMA operation.
@@ -216,11 +726,26 @@ pub enum DmaMode {
     Mode1 = 0x01,
 }
 
+#[allow(non_upper_case_globals)]
+impl DmaMode {
+    /// Single-transfer mode, the same value as [`DmaMode::Mode0`].
+    ///
+    /// The request pins signal whenever one character can be transferred.
+    pub const Single: DmaMode = DmaMode::Mode0;
+
+    /// Multi-transfer mode, the same value as [`DmaMode::Mode1`].
+    ///
+    /// The request pins signal when the receiver FIFO reaches its trigger level or a character timeout occurs,
+    /// and while the transmitter FIFO has room, so the DMA controller moves bursts.
+    pub const Multi: DmaMode = DmaMode::Mode1;
+}
+
 /// Defines the FIFO level at which receiver interrupts are triggered.
 /// Controls receiver sensitivity.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ReceiverTriggerLevel {
     /// Trigger when 1 character is in FIFO.
+    #[default]
     Char1 = 0x00,
     /// Trigger when FIFO is quarter full.
     FifoQuarterFull = 0x01,
@@ -251,9 +776,23 @@ impl IirFcr {
     const XFIFOR: u32 = 0x01 << 2;
     /// DMA Mode Select.
     const DMAM: u32 = 0x01 << 3;
+    /// 64-byte FIFO Enable (16750 only), also reported in the IIR.
+    const FIFO64: u32 = 0x01 << 5;
     /// Receiver Trigger.
     const RT: u32 = 0x03 << 6;
 
+    /// Gets the raw register value.
+    #[inline]
+    pub(crate) const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Creates a value from the raw register bits.
+    #[inline]
+    pub(crate) const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
     /// Gets the interrupt identification from the IIR register.
     /// Returns the type of interrupt that is currently pending.
     #[inline]
@@ -270,14 +809,21 @@ impl IirFcr {
         }
     }
 
+    /// Gets the FIFO status from the IIR register.
+    #[inline]
+    pub const fn fifo_status(self) -> FifoStatus {
+        match (self.0 & Self::FIFOSE) >> 6 {
+            0x00 => FifoStatus::Disabled,
+            0x03 => FifoStatus::Enabled,
+            _ => FifoStatus::EnabledNonFunctioning,
+        }
+    }
+
     /// Checks if FIFOs are enabled.
-    /// Returns true if both transmit and receive FIFOs are enabled.
+    /// Returns true if both transmit and receive FIFOs are enabled and functioning, see [`fifo_status`](Self::fifo_status).
     #[inline]
     pub const fn is_fifos_enabled(self) -> bool {
-        const DISABLE: u32 = 0x00;
-        const ENABLE: u32 = 0x03;
-
-        (self.0 & Self::FIFOSE) == ENABLE << 6
+        matches!(self.fifo_status(), FifoStatus::Enabled)
     }
 
     /// Enables both transmit and receive FIFOs.
@@ -294,6 +840,27 @@ impl IirFcr {
         Self(self.0 & !Self::FIFOE)
     }
 
+    /// Enables the 64-byte FIFOs of a 16750.
+    /// This function sets the FIFO64 bit in the FCR register, which the 16750 only accepts while DLAB is set.
+    #[inline]
+    pub const fn enable_64byte_fifo(self) -> Self {
+        Self(self.0 | Self::FIFO64)
+    }
+
+    /// Disables the 64-byte FIFOs of a 16750.
+    /// This function clears the FIFO64 bit in the FCR register, which the 16750 only accepts while DLAB is set.
+    #[inline]
+    pub const fn disable_64byte_fifo(self) -> Self {
+        Self(self.0 & !Self::FIFO64)
+    }
+
+    /// Checks if the 64-byte FIFOs are enabled.
+    /// Returns true if the FIFO64 bit is set, which a 16750 reports in the IIR.
+    #[inline]
+    pub const fn is_64byte_fifo_enabled(self) -> bool {
+        (self.0 & Self::FIFO64) != 0
+    }
+
     /// Resets the receiver FIFO.
     /// This function sets the RFIFOR bit in the FCR register.
     #[inline]
@@ -315,6 +882,24 @@ impl IirFcr {
         Self((self.0 & !Self::DMAM) | ((val as u32) << 3))
     }
 
+    /// Checks if the FIFOE bit is set.
+    /// This describes a value written to the FCR; use [`is_fifos_enabled`](Self::is_fifos_enabled) on a value read from the IIR.
+    #[inline]
+    pub const fn is_fifo_enable_set(self) -> bool {
+        (self.0 & Self::FIFOE) != 0
+    }
+
+    /// Gets the receiver trigger level from an FCR value.
+    #[inline]
+    pub const fn receiver_trigger_level(self) -> ReceiverTriggerLevel {
+        match (self.0 & Self::RT) >> 6 {
+            0x00 => ReceiverTriggerLevel::Char1,
+            0x01 => ReceiverTriggerLevel::FifoQuarterFull,
+            0x02 => ReceiverTriggerLevel::FifoHalfFull,
+            _ => ReceiverTriggerLevel::FifoFullSub2,
+        }
+    }
+
     /// Sets the receiver trigger level.
     /// This function configures when receiver interrupts are triggered.
     #[inline]
@@ -325,7 +910,8 @@ impl IirFcr {
 
 /// Represents the word length configuration for UART communication.
 /// This enum defines the number of data bits per character.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WordLength {
     /// 5 data bits per character.
     Bits5 = 0x00,
@@ -349,7 +935,8 @@ pub enum Parity {
 
 /// Represents the stop bits configuration for UART communication.
 /// This enum defines the number of stop bits to be used.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StopBits {
     /// One stop bit.
     Bit1 = 0x00,
@@ -357,6 +944,86 @@ pub enum StopBits {
     Bits2OrBits1_5 = 0x01,
 }
 
+/// Represents the stop bits actually sent on the line.
+/// The same LCR setting means 2 stop bits, or 1.5 stop bits with 5-bit words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EffectiveStopBits {
+    /// One stop bit.
+    One,
+    /// One and a half stop bits.
+    OneAndHalf,
+    /// Two stop bits.
+    Two,
+}
+
+impl StopBits {
+    /// Gets the stop bits actually sent for the given word length.
+    #[inline]
+    pub const fn effective(self, word_length: WordLength) -> EffectiveStopBits {
+        match (self, word_length) {
+            (StopBits::Bit1, _) => EffectiveStopBits::One,
+            (StopBits::Bits2OrBits1_5, WordLength::Bits5) => EffectiveStopBits::OneAndHalf,
+            (StopBits::Bits2OrBits1_5, _) => EffectiveStopBits::Two,
+        }
+    }
+}
+
+/// Represents an error when decoding a raw register field value.
+/// Each variant carries the value that could not be decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The value is not a valid word length field (0 to 3).
+    InvalidWordLength(u8),
+    /// The value is not a valid stop bits field (0 or 1).
+    InvalidStopBits(u8),
+    /// The value is not a valid parity field, or is a reserved combination.
+    InvalidParityMode(u8),
+}
+
+impl TryFrom<u8> for WordLength {
+    type Error = DecodeError;
+
+    /// Decodes the word length from the value of the LCR data length select field.
+    #[inline]
+    fn try_from(val: u8) -> Result<Self, Self::Error> {
+        match val {
+            0x00 => Ok(WordLength::Bits5),
+            0x01 => Ok(WordLength::Bits6),
+            0x02 => Ok(WordLength::Bits7),
+            0x03 => Ok(WordLength::Bits8),
+            _ => Err(DecodeError::InvalidWordLength(val)),
+        }
+    }
+}
+
+impl TryFrom<u8> for Parity {
+    type Error = DecodeError;
+
+    /// Decodes the parity from the value of the LCR even parity select bit.
+    #[inline]
+    fn try_from(val: u8) -> Result<Self, Self::Error> {
+        match val {
+            0x00 => Ok(Parity::Odd),
+            0x01 => Ok(Parity::Even),
+            _ => Err(DecodeError::InvalidParityMode(val)),
+        }
+    }
+}
+
+impl TryFrom<u8> for StopBits {
+    type Error = DecodeError;
+
+    /// Decodes the stop bits from the value of the LCR stop bits field.
+    #[inline]
+    fn try_from(val: u8) -> Result<Self, Self::Error> {
+        match val {
+            0x00 => Ok(StopBits::Bit1),
+            0x01 => Ok(StopBits::Bits2OrBits1_5),
+            _ => Err(DecodeError::InvalidStopBits(val)),
+        }
+    }
+}
+
 /// Lcr represents the Line Control Register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
@@ -378,6 +1045,12 @@ impl Lcr {
     /// Divisor Latch Access Bit.
     const DLAB: u32 = 0x01 << 7;
 
+    /// Gets the raw register value.
+    #[inline]
+    pub(crate) const fn bits(self) -> u32 {
+        self.0
+    }
+
     /// Gets the current word length setting.
     /// Returns the word length configuration from the Line Control Register.
     #[inline]
@@ -541,6 +1214,10 @@ impl Mcr {
     const OUT2: u32 = 0x01 << 3;
     /// Loopback Mode.
     const LB: u32 = 0x01 << 4;
+    /// Auto Flow Control Enable (16750 and DesignWare only).
+    const AFE: u32 = 0x01 << 5;
+    /// SIR Mode Enable (DesignWare only).
+    const SIRE: u32 = 0x01 << 6;
 
     /// Gets the Data Terminal Ready (DTR) signal status.
     /// Returns true if DTR is asserted.
@@ -634,6 +1311,48 @@ impl Mcr {
     pub const fn is_loop_back_enabled(self) -> bool {
         (self.0 & Self::LB) != 0
     }
+
+    /// Enables auto flow control.
+    /// With RTS also set, RTS and CTS are then handled by the hardware.
+    /// This bit only exists on the 16750 and on DesignWare UARTs with auto flow control, and is reserved otherwise.
+    #[inline]
+    pub const fn enable_auto_flow_control(self) -> Self {
+        Self(self.0 | Self::AFE)
+    }
+
+    /// Disables auto flow control.
+    #[inline]
+    pub const fn disable_auto_flow_control(self) -> Self {
+        Self(self.0 & !Self::AFE)
+    }
+
+    /// Checks if auto flow control is enabled.
+    /// Returns true if the auto flow control enable bit is set in the Modem Control Register.
+    #[inline]
+    pub const fn is_auto_flow_control_enabled(self) -> bool {
+        (self.0 & Self::AFE) != 0
+    }
+
+    /// Enables IrDA SIR mode.
+    /// This bit only exists on DesignWare UARTs configured with IrDA support and is reserved otherwise.
+    #[inline]
+    pub const fn enable_sir_mode(self) -> Self {
+        Self(self.0 | Self::SIRE)
+    }
+
+    /// Disables IrDA SIR mode.
+    /// This bit only exists on DesignWare UARTs configured with IrDA support and is reserved otherwise.
+    #[inline]
+    pub const fn disable_sir_mode(self) -> Self {
+        Self(self.0 & !Self::SIRE)
+    }
+
+    /// Checks if IrDA SIR mode is enabled.
+    /// Returns true if the SIR mode enable bit is set in the Modem Control Register.
+    #[inline]
+    pub const fn is_sir_mode_enabled(self) -> bool {
+        (self.0 & Self::SIRE) != 0
+    }
 }
 
 /// Lsr represents the Line Status Register.
@@ -643,7 +1362,7 @@ pub struct Lsr(u32);
 
 impl Lsr {
     /// Data Ready.
-    const DR: u32 = 0x01 << 1;
+    const DR: u32 = 0x01 << 0;
     /// Overflow Error.
     const OE: u32 = 0x01 << 1;
     /// Parity Error.
@@ -708,6 +1427,12 @@ impl Lsr {
         (self.0 & Self::TEMT) != 0
     }
 
+    /// Clears the parity error bit of a value read from the LSR.
+    #[inline]
+    pub(crate) const fn without_parity_error(self) -> Self {
+        Self(self.0 & !Self::PE)
+    }
+
     /// Checks if there is an error in the receiver FIFO.
     /// Returns true if at least one character in FIFO has an error.
     #[inline]
@@ -821,6 +1546,7 @@ impl Scr {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
     use core::mem::offset_of;
@@ -834,6 +1560,8 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, lsr), 0x14);
         assert_eq!(offset_of!(RegisterBlock, msr), 0x18);
         assert_eq!(offset_of!(RegisterBlock, scr), 0x1C);
+        assert_eq!(RegOffset::Scr.byte_offset(4), 0x1C);
+        assert_eq!(RegOffset::Lcr.byte_offset(1), 0x03);
     }
     #[test]
     fn struct_rbr_thr_dll_functions() {
@@ -872,6 +1600,12 @@ mod tests {
         val = RbrThrDll(0xFFFF0000);
         val = val.set_divisor_latch_low_byte(0xFF);
         assert_eq!(val.0, 0xFFFF00FF);
+
+        // Test the constructor matches setting the data on a zero value
+        assert_eq!(
+            RbrThrDll::from_transmitter_data(0xA5),
+            RbrThrDll::default().set_transmitter_data(0xA5)
+        );
     }
     #[test]
     fn struct_ier_dlh_functions() {
@@ -980,13 +1714,35 @@ mod tests {
         val = IirFcr(0x00);
         assert!(!val.is_fifos_enabled());
 
+        // Test FIFO status decoding
+        assert_eq!(IirFcr(0xC1).fifo_status(), FifoStatus::Enabled);
+        assert_eq!(
+            IirFcr(0x81).fifo_status(),
+            FifoStatus::EnabledNonFunctioning
+        );
+        assert_eq!(
+            IirFcr(0x41).fifo_status(),
+            FifoStatus::EnabledNonFunctioning
+        );
+        assert_eq!(IirFcr(0x01).fifo_status(), FifoStatus::Disabled);
+
         // Test FIFO enable and disable
         val = IirFcr(0x0);
         val = val.enable_fifo();
         assert_eq!(val.0 & 0x01, 0x01);
+        assert!(val.is_fifo_enable_set());
 
         val = val.disable_fifo();
         assert_eq!(val.0 & 0x01, 0x00);
+        assert!(!val.is_fifo_enable_set());
+
+        // Test 64-byte FIFO enable and disable
+        val = IirFcr(0x0).enable_64byte_fifo();
+        assert_eq!(val.0 & 0x20, 0x20);
+        assert!(val.is_64byte_fifo_enabled());
+
+        val = val.disable_64byte_fifo();
+        assert!(!val.is_64byte_fifo_enabled());
 
         // Test FIFO reset functionality
         val = IirFcr(0x0);
@@ -1019,6 +1775,73 @@ mod tests {
 
         val = val.set_receiver_trigger_level(ReceiverTriggerLevel::FifoFullSub2);
         assert_eq!(val.0 & 0xC0, 0xC0);
+
+        // Test reading back receiver trigger level
+        for level in [
+            ReceiverTriggerLevel::Char1,
+            ReceiverTriggerLevel::FifoQuarterFull,
+            ReceiverTriggerLevel::FifoHalfFull,
+            ReceiverTriggerLevel::FifoFullSub2,
+        ] {
+            assert_eq!(
+                val.set_receiver_trigger_level(level)
+                    .receiver_trigger_level(),
+                level
+            );
+        }
+    }
+
+    #[test]
+    fn enum_stop_bits_effective() {
+        assert_eq!(
+            StopBits::Bit1.effective(WordLength::Bits5),
+            EffectiveStopBits::One
+        );
+        assert_eq!(
+            StopBits::Bit1.effective(WordLength::Bits8),
+            EffectiveStopBits::One
+        );
+        assert_eq!(
+            StopBits::Bits2OrBits1_5.effective(WordLength::Bits5),
+            EffectiveStopBits::OneAndHalf
+        );
+        assert_eq!(
+            StopBits::Bits2OrBits1_5.effective(WordLength::Bits6),
+            EffectiveStopBits::Two
+        );
+        assert_eq!(
+            StopBits::Bits2OrBits1_5.effective(WordLength::Bits8),
+            EffectiveStopBits::Two
+        );
+    }
+
+    #[test]
+    fn enum_try_from_u8() {
+        // Test decoding every valid word length
+        assert_eq!(WordLength::try_from(0x00), Ok(WordLength::Bits5));
+        assert_eq!(WordLength::try_from(0x01), Ok(WordLength::Bits6));
+        assert_eq!(WordLength::try_from(0x02), Ok(WordLength::Bits7));
+        assert_eq!(WordLength::try_from(0x03), Ok(WordLength::Bits8));
+        assert_eq!(
+            WordLength::try_from(0x04),
+            Err(DecodeError::InvalidWordLength(0x04))
+        );
+
+        // Test decoding parity
+        assert_eq!(Parity::try_from(0x00), Ok(Parity::Odd));
+        assert_eq!(Parity::try_from(0x01), Ok(Parity::Even));
+        assert_eq!(
+            Parity::try_from(0x02),
+            Err(DecodeError::InvalidParityMode(0x02))
+        );
+
+        // Test decoding stop bits
+        assert_eq!(StopBits::try_from(0x00), Ok(StopBits::Bit1));
+        assert_eq!(StopBits::try_from(0x01), Ok(StopBits::Bits2OrBits1_5));
+        assert_eq!(
+            StopBits::try_from(0xFF),
+            Err(DecodeError::InvalidStopBits(0xFF))
+        );
     }
 
     #[test]
@@ -1172,12 +1995,31 @@ mod tests {
         val = val.disable_loop_back();
         assert_eq!(val.0, 0x00000000);
         assert_eq!(val.is_loop_back_enabled(), false);
+
+        let mut val = Mcr(0x0);
+
+        // Test enabling and disabling auto flow control
+        val = Mcr(0x0).enable_auto_flow_control();
+        assert_eq!(val.0, 0x00000020);
+        assert!(val.is_auto_flow_control_enabled());
+        val = val.disable_auto_flow_control();
+        assert!(!val.is_auto_flow_control_enabled());
+
+        // Test enabling SIR mode
+        val = val.enable_sir_mode();
+        assert_eq!(val.0, 0x00000040);
+        assert_eq!(val.is_sir_mode_enabled(), true);
+
+        // Test disabling SIR mode
+        val = val.disable_sir_mode();
+        assert_eq!(val.0, 0x00000000);
+        assert_eq!(val.is_sir_mode_enabled(), false);
     }
 
     #[test]
     fn struct_lsr_functions() {
         // Test data ready flag
-        let mut val = Lsr(0x02); // Set DR bit
+        let mut val = Lsr(0x01); // Set DR bit
         assert_eq!(val.is_data_ready(), true);
 
         val = Lsr(0x0);
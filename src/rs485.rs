@@ -0,0 +1,111 @@
+use crate::register::UartRegisters;
+use crate::wait::{DefaultWait, WaitStrategy};
+use crate::{BlockingUart, Hertz, bit_duration_ns, divisor};
+use embedded_hal::delay::DelayNs;
+
+/// A half-duplex RS-485 transmitter, with the driver enable (DE) of the transceiver wired to RTS.
+///
+/// Each [`write`](Self::write) asserts RTS, sends the data, waits until the last stop bit has left
+/// the shift register (TEMT), then waits a turnaround margin before deasserting RTS.
+/// TEMT is set as the stop bit of the last character starts on some parts, and the transceiver
+/// adds its own propagation delay, so deasserting DE right away can clip the last byte on a fast bus.
+/// The margin is given in bit times at the programmed divisor, and defaults to one bit time.
+/// Whether the DE pin is active with RTS set depends on the board; invert it in hardware if needed.
+pub struct Rs485<UART> {
+    uart: BlockingUart<UART>,
+    clock: Hertz,
+    turnaround_bits: u32,
+}
+
+impl<UART: UartRegisters> Rs485<UART> {
+    /// Creates a new Rs485 from a BlockingUart and its input clock, with RTS deasserted.
+    pub fn new(uart: BlockingUart<UART>, clock: Hertz) -> Self {
+        let rs485 = Rs485 {
+            uart,
+            clock,
+            turnaround_bits: 1,
+        };
+        rs485.set_driver_enable(false);
+        rs485
+    }
+
+    /// Sets the margin between TEMT and deasserting RTS, in bit times.
+    ///
+    /// 0 deasserts RTS as soon as TEMT is seen.
+    pub fn set_turnaround_bits(&mut self, bits: u32) {
+        self.turnaround_bits = bits;
+    }
+
+    /// Sends all data with the driver enabled, then releases the bus.
+    ///
+    /// The margin is timed with `delay`, from the divisor read back from the UART,
+    /// which briefly sets DLAB before the driver is enabled.
+    pub fn write(&mut self, buf: &[u8], delay: &mut impl DelayNs) {
+        let bit_ns = bit_duration_ns(self.clock.0, divisor(self.uart.registers()));
+        self.set_driver_enable(true);
+        let mut rest = buf;
+        while !rest.is_empty() {
+            let len = self.uart.write(rest);
+            if len == 0 {
+                DefaultWait::wait();
+            }
+            rest = &rest[len..];
+        }
+        self.uart.flush();
+        delay.delay_ns(bit_ns.saturating_mul(self.turnaround_bits));
+        self.set_driver_enable(false);
+    }
+
+    /// Returns a mutable reference to the wrapped BlockingUart.
+    pub fn inner_mut(&mut self) -> &mut BlockingUart<UART> {
+        &mut self.uart
+    }
+
+    /// Consumes the Rs485, returning the wrapped BlockingUart.
+    pub fn into_inner(self) -> BlockingUart<UART> {
+        self.uart
+    }
+
+    fn set_driver_enable(&self, enable: bool) {
+        let uart = self.uart.registers();
+        let mcr = uart.read_mcr().set_request_to_send(enable);
+        unsafe {
+            uart.write_mcr(mcr);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use crate::mock::MockUart;
+
+    /// Records the total time waited.
+    struct RecordDelay(u64);
+
+    impl DelayNs for RecordDelay {
+        fn delay_ns(&mut self, ns: u32) {
+            self.0 += ns as u64;
+        }
+    }
+
+    #[test]
+    fn rs485_turnaround() {
+        let mock = MockUart::new();
+        let uart = BlockingUart::new(&mock, Config::new().set_divisor(1), false);
+        let mut rs485 = Rs485::new(uart, Hertz(1_843_200));
+        let mut delay = RecordDelay(0);
+
+        // Test the bus is released one bit time after the data at 115200 baud
+        rs485.write(b"ab", &mut delay);
+        assert_eq!(delay.0, 8_680);
+        assert!(!mock.regs.mcr.read().request_to_send());
+        assert_eq!(mock.regs.rbr_thr_dll.read().receiver_data(), b'b');
+
+        // Test a wider margin
+        rs485.set_turnaround_bits(3);
+        rs485.write(b"c", &mut delay);
+        assert_eq!(delay.0, 4 * 8_680);
+    }
+}
@@ -0,0 +1,56 @@
+/// A strategy for waiting in the busy loops of blocking operations.
+///
+/// The blocking read, write and flush loops call [`wait`](Self::wait) between polls of the Line Status Register.
+/// The strategy is selected at compile time: [`Spin`] by default, or [`Wfi`] with the `wfi` feature.
+pub trait WaitStrategy {
+    /// Waits before the next poll.
+    fn wait();
+}
+
+/// Busy-waits using a spin loop hint.
+pub struct Spin;
+
+impl WaitStrategy for Spin {
+    #[inline]
+    fn wait() {
+        core::hint::spin_loop();
+    }
+}
+
+/// Waits for an interrupt using the low power `wfi` instruction.
+///
+/// The core sleeps until the next interrupt, so a blocking operation only makes progress
+/// if some interrupt fires while it waits: typically the UART's own interrupts must be enabled,
+/// or a periodic timer interrupt must be running. Otherwise the operation never returns.
+/// On targets without a wait-for-interrupt instruction this falls back to a spin loop hint.
+pub struct Wfi;
+
+impl WaitStrategy for Wfi {
+    #[inline]
+    fn wait() {
+        #[cfg(any(
+            target_arch = "arm",
+            target_arch = "aarch64",
+            target_arch = "riscv32",
+            target_arch = "riscv64"
+        ))]
+        unsafe {
+            core::arch::asm!("wfi", options(nomem, nostack, preserves_flags));
+        }
+        #[cfg(not(any(
+            target_arch = "arm",
+            target_arch = "aarch64",
+            target_arch = "riscv32",
+            target_arch = "riscv64"
+        )))]
+        core::hint::spin_loop();
+    }
+}
+
+/// The wait strategy used by the blocking operations.
+#[cfg(not(feature = "wfi"))]
+pub(crate) type DefaultWait = Spin;
+
+/// The wait strategy used by the blocking operations.
+#[cfg(feature = "wfi")]
+pub(crate) type DefaultWait = Wfi;